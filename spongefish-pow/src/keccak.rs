@@ -11,8 +11,13 @@ impl PowStrategy for KeccakPoW {
     #[allow(clippy::cast_sign_loss)]
     fn new(challenge: [u8; 32], bits: f64) -> Self {
         let threshold = (64.0 - bits).exp2().ceil() as u64;
+        // Keccak lanes are little-endian, whatever the target.
+        let mut lanes = [0u64; 4];
+        for (lane, chunk) in lanes.iter_mut().zip(challenge.chunks_exact(8)) {
+            *lane = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
         Self {
-            challenge: bytemuck::cast(challenge),
+            challenge: lanes,
             threshold,
             state: [0; 25],
         }
@@ -29,6 +34,25 @@ impl PowStrategy for KeccakPoW {
     }
 }
 
+#[test]
+fn test_pow_keccak_challenge_lanes() {
+    let mut challenge = [0u8; 32];
+    challenge[0] = 1;
+    challenge[31] = 0x80;
+    let pow = KeccakPoW::new(challenge, 1.0);
+    assert_eq!(pow.challenge, [1, 0, 0, 0x8000_0000_0000_0000]);
+}
+
+#[test]
+fn test_pow_keccak_lanes_are_values() {
+    // The state is only handled as lane values, so that only `new` depends on the byte order:
+    // with a zero challenge and nonce, the first lane is the one of Keccak-f[1600]
+    // applied to the all-zero state, whatever the target.
+    let mut pow = KeccakPoW::new([0; 32], 1.0);
+    assert!(!pow.check(0));
+    assert_eq!(pow.state[0], 0xF125_8F79_40E1_DDE7);
+}
+
 #[test]
 fn test_pow_keccak() {
     use spongefish::{DefaultHash, DomainSeparator};
@@ -3,7 +3,7 @@ pub mod keccak;
 
 use spongefish::{
     ByteDomainSeparator, BytesToUnitDeserialize, BytesToUnitSerialize, DuplexSpongeInterface,
-    ProofError, ProofResult, ProverState, Unit, UnitToBytes, VerifierState,
+    NargSource, ProofError, ProofResult, ProverState, Unit, UnitToBytes, VerifierState,
 };
 
 /// [`spongefish::DomainSeparator`] for proof-of-work challenges.
@@ -37,11 +37,13 @@ pub trait PoWChallenge {
     fn challenge_pow<S: PowStrategy>(&mut self, bits: f64) -> ProofResult<()>;
 }
 
-impl<H, U, R> PoWChallenge for ProverState<H, U, R>
+impl<H, U, R, P, W> PoWChallenge for ProverState<H, U, R, P, W>
 where
     U: Unit,
     H: DuplexSpongeInterface<U>,
     R: rand::CryptoRng + rand::RngCore,
+    P: DuplexSpongeInterface,
+    W: spongefish::io::Write,
     Self: BytesToUnitSerialize + UnitToBytes,
 {
     fn challenge_pow<S: PowStrategy>(&mut self, bits: f64) -> ProofResult<()> {
@@ -54,11 +56,12 @@ where
     }
 }
 
-impl<H, U> PoWChallenge for VerifierState<'_, H, U>
+impl<H, U, R> PoWChallenge for VerifierState<'_, H, U, R>
 where
     U: Unit,
     H: DuplexSpongeInterface<U>,
     Self: BytesToUnitDeserialize + UnitToBytes,
+    R: NargSource,
 {
     fn challenge_pow<S: PowStrategy>(&mut self, bits: f64) -> ProofResult<()> {
         let challenge = self.challenge_bytes()?;
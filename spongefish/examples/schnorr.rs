@@ -190,4 +190,6 @@ fn main() {
     verifier_state.public_points(&[P, X]).unwrap();
     verifier_state.ratchet().unwrap();
     verify(&mut verifier_state, P, X).expect("Invalid proof");
+    // Make sure the whole proof has been read, and nothing was appended to it.
+    verifier_state.finish().expect("Invalid proof");
 }
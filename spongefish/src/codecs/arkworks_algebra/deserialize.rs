@@ -1,21 +1,25 @@
+use alloc::{vec, vec::Vec};
+
 use ark_ec::{
     short_weierstrass::{Affine as SWAffine, Projective as SWCurve, SWCurveConfig},
     twisted_edwards::{Affine as EdwardsAffine, Projective as EdwardsCurve, TECurveConfig},
     CurveGroup,
 };
 use ark_ff::{Field, Fp, FpConfig};
-use ark_serialize::CanonicalDeserialize;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 
 use super::{FieldToUnitDeserialize, GroupToUnitDeserialize};
 use crate::{
     traits::{BytesToUnitDeserialize, UnitTranscript},
-    DuplexSpongeInterface, ProofResult, VerifierState,
+    DomainSeparatorMismatch, DuplexSpongeInterface, NargSource, ProofError, ProofResult,
+    VerifierState,
 };
 
-impl<F, H> FieldToUnitDeserialize<F> for VerifierState<'_, H>
+impl<F, H, R> FieldToUnitDeserialize<F> for VerifierState<'_, H, u8, R>
 where
     F: Field,
     H: DuplexSpongeInterface,
+    R: NargSource,
 {
     fn fill_next_scalars(&mut self, output: &mut [F]) -> ProofResult<()> {
         let point_size = F::default().compressed_size();
@@ -26,12 +30,28 @@ where
         }
         Ok(())
     }
+
+    fn next_scalars_vec_bounded(&mut self, max_len: usize) -> ProofResult<Vec<F>> {
+        let point_size = F::default().compressed_size();
+        let len = self.next_units_len_bounded(max_len.saturating_mul(point_size))?;
+        if len % point_size != 0 {
+            return Err(ProofError::SerializationError(None));
+        }
+        let mut buf = vec![0u8; point_size];
+        let mut output = Vec::new();
+        for _ in 0..len / point_size {
+            self.fill_next_bytes(&mut buf)?;
+            output.push(F::deserialize_compressed(buf.as_slice())?);
+        }
+        Ok(output)
+    }
 }
 
-impl<G, H> GroupToUnitDeserialize<G> for VerifierState<'_, H>
+impl<G, H, R> GroupToUnitDeserialize<G> for VerifierState<'_, H, u8, R>
 where
     G: CurveGroup,
     H: DuplexSpongeInterface,
+    R: NargSource,
 {
     fn fill_next_points(&mut self, output: &mut [G]) -> ProofResult<()> {
         let point_size = G::default().compressed_size();
@@ -45,27 +65,37 @@ where
     }
 }
 
-impl<H, C, const N: usize> FieldToUnitDeserialize<Fp<C, N>> for VerifierState<'_, H, Fp<C, N>>
+impl<H, C, const N: usize, R> FieldToUnitDeserialize<Fp<C, N>> for VerifierState<'_, H, Fp<C, N>, R>
 where
     C: FpConfig<N>,
     H: DuplexSpongeInterface<Fp<C, N>>,
+    R: NargSource,
 {
     fn fill_next_scalars(&mut self, output: &mut [Fp<C, N>]) -> crate::ProofResult<()> {
         self.fill_next_units(output)?;
         Ok(())
     }
+
+    fn next_scalars_vec_bounded(&mut self, max_len: usize) -> ProofResult<Vec<Fp<C, N>>> {
+        Ok(self.next_units_vec_bounded(max_len)?)
+    }
 }
 
-impl<P, H, C, const N: usize> GroupToUnitDeserialize<EdwardsCurve<P>>
-    for VerifierState<'_, H, Fp<C, N>>
+impl<P, H, C, const N: usize, R> GroupToUnitDeserialize<EdwardsCurve<P>>
+    for VerifierState<'_, H, Fp<C, N>, R>
 where
     C: FpConfig<N>,
     H: DuplexSpongeInterface<Fp<C, N>>,
     P: TECurveConfig<BaseField = Fp<C, N>>,
+    R: NargSource,
 {
     fn fill_next_points(&mut self, output: &mut [EdwardsCurve<P>]) -> ProofResult<()> {
+        let mut buf = vec![0u8; EdwardsAffine::<P>::default().compressed_size()];
         for o in output.iter_mut() {
-            let o_affine = EdwardsAffine::deserialize_compressed(&mut self.narg_string)?;
+            self.narg_string
+                .read_exact(&mut buf)
+                .map_err(DomainSeparatorMismatch::from)?;
+            let o_affine = EdwardsAffine::deserialize_compressed(buf.as_slice())?;
             *o = o_affine.into();
             self.public_units(&[o.x, o.y])?;
         }
@@ -73,15 +103,21 @@ where
     }
 }
 
-impl<P, H, C, const N: usize> GroupToUnitDeserialize<SWCurve<P>> for VerifierState<'_, H, Fp<C, N>>
+impl<P, H, C, const N: usize, R> GroupToUnitDeserialize<SWCurve<P>>
+    for VerifierState<'_, H, Fp<C, N>, R>
 where
     C: FpConfig<N>,
     H: DuplexSpongeInterface<Fp<C, N>>,
     P: SWCurveConfig<BaseField = Fp<C, N>>,
+    R: NargSource,
 {
     fn fill_next_points(&mut self, output: &mut [SWCurve<P>]) -> ProofResult<()> {
+        let mut buf = vec![0u8; SWAffine::<P>::default().compressed_size()];
         for o in output.iter_mut() {
-            let o_affine = SWAffine::deserialize_compressed(&mut self.narg_string)?;
+            self.narg_string
+                .read_exact(&mut buf)
+                .map_err(DomainSeparatorMismatch::from)?;
+            let o_affine = SWAffine::deserialize_compressed(buf.as_slice())?;
             *o = o_affine.into();
             self.public_units(&[o.x, o.y])?;
         }
@@ -138,6 +174,59 @@ mod tests {
         assert_eq!(out, scalars, "Deserialized scalars do not match original");
     }
 
+    #[test]
+    fn test_fill_next_from_reader() {
+        use ark_bls12_381::Fr as F;
+
+        let mut rng = ark_std::test_rng();
+        let scalars = [F::rand(&mut rng), F::rand(&mut rng)];
+        let point = G1Projective::rand(&mut rng);
+        let mut raw_bytes = Vec::new();
+        scalars.serialize_compressed(&mut raw_bytes).unwrap();
+        point
+            .into_affine()
+            .serialize_compressed(&mut raw_bytes)
+            .unwrap();
+
+        let domsep = DomainSeparator::<DefaultHash>::new("stream");
+        let domsep = FieldDomainSeparator::<F>::add_scalars(domsep, 2, "x");
+        let domsep = GroupDomainSeparator::<G1Projective>::add_points(domsep, 1, "P");
+
+        let mut verifier = VerifierState::from_reader(&domsep, std::io::Cursor::new(&raw_bytes));
+        let out: [F; 2] = verifier.next_scalars().unwrap();
+        let [p]: [G1Projective; 1] = verifier.next_points().unwrap();
+        assert_eq!(out, scalars);
+        assert_eq!(p, point);
+        verifier.finish().unwrap();
+    }
+
+    #[test]
+    fn test_fill_next_scalars_invalid_encoding_keeps_source() {
+        use std::error::Error;
+
+        use ark_bls12_381::Fr as F;
+
+        // 0xff..ff is larger than the modulus
+        let raw_bytes = [0xffu8; 32];
+        let domsep = <DomainSeparator as FieldDomainSeparator<F>>::add_scalars(
+            DomainSeparator::<DefaultHash>::new("read"),
+            1,
+            "scalar",
+        );
+
+        let mut verifier = domsep.to_verifier_state(&raw_bytes);
+        let err = verifier.fill_next_scalars(&mut [F::ZERO; 1]).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::ProofError::SerializationError(Some(_))
+        ));
+        assert!(err
+            .source()
+            .unwrap()
+            .downcast_ref::<ark_serialize::SerializationError>()
+            .is_some());
+    }
+
     #[test]
     fn test_fill_next_scalars_fp_unit() {
         let mut rng = ark_std::test_rng();
@@ -5,7 +5,10 @@ use super::{
     ByteDomainSeparator, DomainSeparator, DuplexSpongeInterface, FieldDomainSeparator,
     GroupDomainSeparator,
 };
-use crate::codecs::{bytes_modp, bytes_uniform_modp};
+use crate::{
+    codecs::{bytes_modp, bytes_uniform_modp, checked_count},
+    DomainSeparatorMismatch,
+};
 
 impl<F, H> FieldDomainSeparator<F> for DomainSeparator<H>
 where
@@ -13,21 +16,36 @@ where
     H: DuplexSpongeInterface,
 {
     fn add_scalars(self, count: usize, label: &str) -> Self {
-        self.add_bytes(
-            count
-                * F::extension_degree() as usize
-                * bytes_modp(F::BasePrimeField::MODULUS_BIT_SIZE),
-            label,
-        )
+        <Self as FieldDomainSeparator<F>>::try_add_scalars(self, count, label)
+            .unwrap_or_else(|e| panic!("{e}"))
     }
 
     fn challenge_scalars(self, count: usize, label: &str) -> Self {
-        self.challenge_bytes(
-            count
-                * F::extension_degree() as usize
-                * bytes_uniform_modp(F::BasePrimeField::MODULUS_BIT_SIZE),
-            label,
-        )
+        <Self as FieldDomainSeparator<F>>::try_challenge_scalars(self, count, label)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn try_add_scalars(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        let size = F::extension_degree() as usize * bytes_modp(F::BasePrimeField::MODULUS_BIT_SIZE);
+        self.try_add_bytes(checked_count(count, size)?, label)
+    }
+
+    fn try_challenge_scalars(
+        self,
+        count: usize,
+        label: &str,
+    ) -> Result<Self, DomainSeparatorMismatch> {
+        let size = F::extension_degree() as usize
+            * bytes_uniform_modp(F::BasePrimeField::MODULUS_BIT_SIZE);
+        self.try_challenge_bytes(checked_count(count, size)?, label)
+    }
+
+    fn try_hint_scalars(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_hint_bounded(checked_count(count, F::default().compressed_size())?, label)
+    }
+
+    fn try_add_scalars_dyn(self, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_absorb_dyn(label)
     }
 }
 
@@ -38,11 +56,33 @@ where
     H: DuplexSpongeInterface<Fp<C, N>>,
 {
     fn add_scalars(self, count: usize, label: &str) -> Self {
-        self.absorb(count * F::extension_degree() as usize, label)
+        <Self as FieldDomainSeparator<F>>::try_add_scalars(self, count, label)
+            .unwrap_or_else(|e| panic!("{e}"))
     }
 
     fn challenge_scalars(self, count: usize, label: &str) -> Self {
-        self.squeeze(count * F::extension_degree() as usize, label)
+        <Self as FieldDomainSeparator<F>>::try_challenge_scalars(self, count, label)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn try_add_scalars(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_absorb(checked_count(count, F::extension_degree() as usize)?, label)
+    }
+
+    fn try_challenge_scalars(
+        self,
+        count: usize,
+        label: &str,
+    ) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_squeeze(checked_count(count, F::extension_degree() as usize)?, label)
+    }
+
+    fn try_hint_scalars(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_hint_bounded(checked_count(count, F::default().compressed_size())?, label)
+    }
+
+    fn try_add_scalars_dyn(self, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_absorb_dyn(label)
     }
 }
 
@@ -61,8 +101,33 @@ where
     }
 
     fn challenge_bytes(self, count: usize, label: &str) -> Self {
+        self.try_challenge_bytes(count, label)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn try_add_bytes(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_absorb(count, label)
+    }
+
+    fn try_hint(self, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_hint(label)
+    }
+
+    fn try_hint_bounded(
+        self,
+        max_len: usize,
+        label: &str,
+    ) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_hint_bounded(max_len, label)
+    }
+
+    fn try_challenge_bytes(
+        self,
+        count: usize,
+        label: &str,
+    ) -> Result<Self, DomainSeparatorMismatch> {
         let n = crate::codecs::random_bits_in_random_modp(Fp::<C, N>::MODULUS) / 8;
-        self.squeeze(count.div_ceil(n), label)
+        self.try_squeeze(count.div_ceil(n), label)
     }
 }
 
@@ -72,7 +137,16 @@ where
     H: DuplexSpongeInterface,
 {
     fn add_points(self, count: usize, label: &str) -> Self {
-        self.add_bytes(count * G::default().compressed_size(), label)
+        <Self as GroupDomainSeparator<G>>::try_add_points(self, count, label)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn try_add_points(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_add_bytes(checked_count(count, G::default().compressed_size())?, label)
+    }
+
+    fn try_hint_points(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_hint_bounded(checked_count(count, G::default().compressed_size())?, label)
     }
 }
 
@@ -84,7 +158,16 @@ where
     Self: FieldDomainSeparator<Fp<C, N>>,
 {
     fn add_points(self, count: usize, label: &str) -> Self {
-        self.absorb(count * 2, label)
+        <Self as GroupDomainSeparator<G>>::try_add_points(self, count, label)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn try_add_points(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_absorb(checked_count(count, 2)?, label)
+    }
+
+    fn try_hint_points(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_hint_bounded(checked_count(count, G::default().compressed_size())?, label)
     }
 }
 
@@ -271,6 +354,15 @@ mod tests {
         assert_eq!(point_sep.as_bytes(), byte_sep.as_bytes());
     }
 
+    #[test]
+    fn test_hint_scalars_and_points_record_the_bound() {
+        let sep = DomainSeparator::<DefaultHash>::new("hints");
+        let sep = <DomainSeparator as FieldDomainSeparator<Fq2>>::hint_scalars(sep, 3, "aux");
+        let sep = <DomainSeparator as GroupDomainSeparator<Curve>>::hint_points(sep, 2, "path");
+        // Fq2 elements take 2 × 48 bytes, curve25519 points take 32 bytes.
+        assert_eq!(sep.as_bytes(), b"hints\0H288aux\0H64path");
+    }
+
     #[test]
     fn test_domain_separator_determinism() {
         type G = Curve;
@@ -474,4 +566,34 @@ mod tests {
         let expected = b"ext2\0S76b";
         assert_eq!(sep.as_bytes(), expected);
     }
+
+    #[test]
+    fn test_try_add_scalars_errors_instead_of_panicking() {
+        // Overflowing counts and invalid labels are reported as errors.
+        let sep = DomainSeparator::<DefaultHash>::new("err");
+        assert!(
+            <DomainSeparator as FieldDomainSeparator<Fr>>::try_add_scalars(
+                sep.clone(),
+                usize::MAX,
+                "x"
+            )
+            .is_err()
+        );
+        assert!(
+            <DomainSeparator as FieldDomainSeparator<Fr>>::try_challenge_scalars(
+                sep.clone(),
+                1,
+                "1x"
+            )
+            .is_err()
+        );
+        assert!(
+            <DomainSeparator as GroupDomainSeparator<Curve>>::try_add_points(sep.clone(), 0, "x")
+                .is_err()
+        );
+
+        let sep =
+            <DomainSeparator as FieldDomainSeparator<Fr>>::try_add_scalars(sep, 1, "x").unwrap();
+        assert_eq!(sep.as_bytes(), b"err\0A32x");
+    }
 }
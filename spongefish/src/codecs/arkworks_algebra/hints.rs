@@ -0,0 +1,274 @@
+use alloc::vec::Vec;
+
+use ark_ec::{short_weierstrass, twisted_edwards, CurveGroup};
+use ark_ff::{CubicExtConfig, CubicExtField, Field, Fp, FpConfig, QuadExtConfig, QuadExtField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    io, DuplexSpongeInterface, NargSource, ProofError, ProofResult, ProverState, Unit,
+    VerifierState,
+};
+
+/// Send hints serialized with arkworks.
+///
+/// A hint holds the compressed encoding of its value, prefixed by its length as every hint.
+/// Hints are not absorbed in the sponge: the protocol must check them against the transcript.
+pub trait HintSerialize {
+    /// Hint any value serializable with arkworks, e.g. a Merkle authentication path.
+    fn hint<T: CanonicalSerialize>(&mut self, hint: &T) -> ProofResult<()>;
+
+    /// Hint field elements, as the concatenation of their compressed encodings.
+    ///
+    /// This matches the bound set by [`FieldDomainSeparator::hint_scalars`](super::FieldDomainSeparator::hint_scalars).
+    fn hint_scalars<F: Field>(&mut self, hint: &[F]) -> ProofResult<()>;
+
+    /// Hint group elements, as the concatenation of their compressed encodings.
+    ///
+    /// This matches the bound set by [`GroupDomainSeparator::hint_points`](super::GroupDomainSeparator::hint_points).
+    fn hint_points<G: CurveGroup>(&mut self, hint: &[G]) -> ProofResult<()>;
+}
+
+/// Receive (and deserialize) hints sent with [`HintSerialize`].
+///
+/// Hints are deserialized with the same validity checks as prover messages,
+/// and must be consumed entirely.
+pub trait HintDeserialize {
+    /// Deserialize a hint into a value serialized with arkworks.
+    ///
+    /// The value is decoded with [`BoundedDeserialize`], so that the length announced by
+    /// a collection cannot make the verifier allocate more than the hint holds.
+    fn hint<T: BoundedDeserialize>(&mut self) -> ProofResult<T>;
+
+    /// Deserialize a hint into field elements.
+    fn hint_scalars<F: Field>(&mut self) -> ProofResult<Vec<F>>;
+
+    /// Deserialize a hint into group elements.
+    fn hint_points<G: CurveGroup>(&mut self) -> ProofResult<Vec<G>>;
+}
+
+impl<H, U, R, P, W> HintSerialize for ProverState<H, U, R, P, W>
+where
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+    R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
+{
+    fn hint<T: CanonicalSerialize>(&mut self, hint: &T) -> ProofResult<()> {
+        let mut buf = Vec::with_capacity(hint.compressed_size());
+        hint.serialize_compressed(&mut buf)?;
+        Ok(self.hint_bytes(&buf)?)
+    }
+
+    fn hint_scalars<F: Field>(&mut self, hint: &[F]) -> ProofResult<()> {
+        let mut buf = Vec::with_capacity(hint.len() * F::default().compressed_size());
+        for scalar in hint {
+            scalar.serialize_compressed(&mut buf)?;
+        }
+        Ok(self.hint_bytes(&buf)?)
+    }
+
+    fn hint_points<G: CurveGroup>(&mut self, hint: &[G]) -> ProofResult<()> {
+        let mut buf = Vec::with_capacity(hint.len() * G::default().compressed_size());
+        for point in hint {
+            point.serialize_compressed(&mut buf)?;
+        }
+        Ok(self.hint_bytes(&buf)?)
+    }
+}
+
+impl<H, U, R> HintDeserialize for VerifierState<'_, H, U, R>
+where
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+    R: NargSource,
+    R::Hint: AsRef<[u8]>,
+{
+    fn hint<T: BoundedDeserialize>(&mut self) -> ProofResult<T> {
+        let hint = self.hint_bytes()?;
+        let mut bytes = hint.as_ref();
+        let value = T::deserialize_bounded(&mut bytes)?;
+        // the encoding is canonical only if there is nothing left
+        if !bytes.is_empty() {
+            return Err(ProofError::SerializationError(None));
+        }
+        Ok(value)
+    }
+
+    fn hint_scalars<F: Field>(&mut self) -> ProofResult<Vec<F>> {
+        let hint = self.hint_bytes()?;
+        deserialize_elements(hint.as_ref(), F::default().compressed_size())
+    }
+
+    fn hint_points<G: CurveGroup>(&mut self) -> ProofResult<Vec<G>> {
+        let hint = self.hint_bytes()?;
+        deserialize_elements(hint.as_ref(), G::default().compressed_size())
+    }
+}
+
+/// Values deserialized from a hint without allocating more than the hint holds.
+///
+/// Arkworks decodes a [`Vec`] by allocating the number of elements announced in its encoding,
+/// which is untrusted in a hint. Implementations decode the compressed arkworks encoding,
+/// but reject collections announcing more elements than there are bytes left.
+/// Types without collections opt in with the provided method, which decodes them with arkworks.
+pub trait BoundedDeserialize: CanonicalDeserialize {
+    /// Deserialize a value from the front of `bytes`, advancing it past the value.
+    fn deserialize_bounded(bytes: &mut &[u8]) -> ProofResult<Self> {
+        Ok(Self::deserialize_compressed(bytes)?)
+    }
+}
+
+impl<T: BoundedDeserialize> BoundedDeserialize for Vec<T> {
+    fn deserialize_bounded(bytes: &mut &[u8]) -> ProofResult<Self> {
+        let len = u64::deserialize_compressed(&mut *bytes)?;
+        if len > bytes.len() as u64 {
+            return Err(ProofError::SerializationError(None));
+        }
+        (0..len).map(|_| T::deserialize_bounded(bytes)).collect()
+    }
+}
+
+impl<T: BoundedDeserialize, const N: usize> BoundedDeserialize for [T; N] {
+    fn deserialize_bounded(bytes: &mut &[u8]) -> ProofResult<Self> {
+        let values = (0..N)
+            .map(|_| T::deserialize_bounded(bytes))
+            .collect::<ProofResult<Vec<T>>>()?;
+        values
+            .try_into()
+            .map_err(|_| ProofError::SerializationError(None))
+    }
+}
+
+impl BoundedDeserialize for bool {}
+impl BoundedDeserialize for u8 {}
+impl BoundedDeserialize for u16 {}
+impl BoundedDeserialize for u32 {}
+impl BoundedDeserialize for u64 {}
+impl BoundedDeserialize for usize {}
+impl<P: FpConfig<N>, const N: usize> BoundedDeserialize for Fp<P, N> {}
+impl<P: QuadExtConfig> BoundedDeserialize for QuadExtField<P> {}
+impl<P: CubicExtConfig> BoundedDeserialize for CubicExtField<P> {}
+impl<P: short_weierstrass::SWCurveConfig> BoundedDeserialize for short_weierstrass::Affine<P> {}
+impl<P: short_weierstrass::SWCurveConfig> BoundedDeserialize for short_weierstrass::Projective<P> {}
+impl<P: twisted_edwards::TECurveConfig> BoundedDeserialize for twisted_edwards::Affine<P> {}
+impl<P: twisted_edwards::TECurveConfig> BoundedDeserialize for twisted_edwards::Projective<P> {}
+
+/// Deserialize the concatenation of compressed elements of `size` bytes each.
+fn deserialize_elements<T: CanonicalDeserialize>(bytes: &[u8], size: usize) -> ProofResult<Vec<T>> {
+    if bytes.len() % size != 0 {
+        return Err(ProofError::SerializationError(None));
+    }
+    bytes
+        .chunks_exact(size)
+        .map(|chunk| Ok(T::deserialize_compressed(chunk)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Fr, G1Projective};
+    use ark_ec::PrimeGroup;
+    use ark_ff::UniformRand;
+
+    use super::*;
+    use crate::{
+        codecs::arkworks_algebra::{FieldDomainSeparator, GroupDomainSeparator},
+        DefaultHash, DomainSeparator,
+    };
+
+    #[test]
+    fn test_hints_round_trip() {
+        let domsep = DomainSeparator::<DefaultHash>::new("hints")
+            .hint("path")
+            .hint("authentication path");
+        let domsep = FieldDomainSeparator::<Fr>::hint_scalars(domsep, 2, "scalars");
+        let domsep = GroupDomainSeparator::<G1Projective>::hint_points(domsep, 3, "points");
+
+        let mut rng = ark_std::test_rng();
+        let path = [[1u8; 32], [2u8; 32]];
+        let authentication_path = vec![[3u8; 32], [4u8; 32], [5u8; 32]];
+        let scalars = [Fr::rand(&mut rng), Fr::rand(&mut rng)];
+        let points = [G1Projective::generator() * scalars[0]; 2];
+
+        let mut prover_state = domsep.to_prover_state();
+        prover_state.hint(&path).unwrap();
+        prover_state.hint(&authentication_path).unwrap();
+        prover_state.hint_scalars(&scalars).unwrap();
+        prover_state.hint_points(&points).unwrap();
+        let narg_string = prover_state.finish().unwrap();
+
+        let mut verifier_state = domsep.to_verifier_state(&narg_string);
+        assert_eq!(verifier_state.hint::<[[u8; 32]; 2]>().unwrap(), path);
+        assert_eq!(
+            verifier_state.hint::<Vec<[u8; 32]>>().unwrap(),
+            authentication_path
+        );
+        assert_eq!(verifier_state.hint_scalars::<Fr>().unwrap(), scalars);
+        assert_eq!(
+            verifier_state.hint_points::<G1Projective>().unwrap(),
+            points
+        );
+        verifier_state.finish().unwrap();
+    }
+
+    #[test]
+    fn test_hints_are_bounded() {
+        let domsep = FieldDomainSeparator::<Fr>::hint_scalars(
+            DomainSeparator::<DefaultHash>::new("hints"),
+            1,
+            "scalars",
+        );
+        let mut prover_state = domsep.to_prover_state();
+        assert!(prover_state.hint_scalars(&[Fr::from(1); 2]).is_err());
+    }
+
+    #[test]
+    fn test_malformed_hints_are_rejected() {
+        let domsep = DomainSeparator::<DefaultHash>::new("hints").hint("h");
+        let hint = |bytes: &[u8]| {
+            let mut narg_string = (bytes.len() as u32).to_le_bytes().to_vec();
+            narg_string.extend_from_slice(bytes);
+            narg_string
+        };
+
+        // A truncated element.
+        let narg_string = hint(&[0; 31]);
+        let mut verifier_state = domsep.to_verifier_state(&narg_string);
+        assert!(verifier_state.hint_scalars::<Fr>().is_err());
+
+        // A non-canonical field element.
+        let narg_string = hint(&[0xff; 32]);
+        let mut verifier_state = domsep.to_verifier_state(&narg_string);
+        assert!(verifier_state.hint_scalars::<Fr>().is_err());
+
+        // A point not on the curve.
+        let mut bytes = vec![0u8; 48];
+        bytes[0] = 0x83;
+        let narg_string = hint(&bytes);
+        let mut verifier_state = domsep.to_verifier_state(&narg_string);
+        assert!(verifier_state.hint_points::<G1Projective>().is_err());
+
+        // An arkworks vector announcing 2^60 elements is not decoded.
+        let narg_string = hint(&(1u64 << 60).to_le_bytes());
+        let mut verifier_state = domsep.to_verifier_state(&narg_string);
+        assert!(verifier_state.hint_scalars::<Fr>().is_err());
+        let mut verifier_state = domsep.to_verifier_state(&narg_string);
+        assert!(verifier_state.hint::<Vec<[u8; 32]>>().is_err());
+        let mut verifier_state = domsep.to_verifier_state(&narg_string);
+        assert!(verifier_state.hint::<Vec<Vec<u8>>>().is_err());
+
+        // A vector announcing more elements than it holds.
+        let mut bytes = 2u64.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&[0; 32]);
+        let narg_string = hint(&bytes);
+        let mut verifier_state = domsep.to_verifier_state(&narg_string);
+        assert!(verifier_state.hint::<Vec<[u8; 32]>>().is_err());
+
+        // Trailing bytes after the value.
+        let narg_string = hint(&[0; 33]);
+        let mut verifier_state = domsep.to_verifier_state(&narg_string);
+        assert!(verifier_state.hint::<Fr>().is_err());
+    }
+}
@@ -124,13 +124,19 @@ mod verifier_messages;
 
 /// Veririfer's utilities for decoding a transcript.
 mod deserialize;
+/// Hints serialized with arkworks.
+mod hints;
 /// Prover's utilities for encoding into a transcript.
 mod prover_messages;
+/// Stable identifiers of arkworks fields and groups in interaction patterns.
+mod transcript_type;
 
 /// Tests for arkworks.
 #[cfg(test)]
 mod tests;
 
+pub use hints::{BoundedDeserialize, HintDeserialize, HintSerialize};
+
 pub use crate::{
     duplex_sponge::Unit, traits::*, DomainSeparator, DuplexSpongeInterface,
     HashStateWithInstructions, ProofError, ProofResult, ProverState, VerifierState,
@@ -149,7 +155,7 @@ pub fn swap_field<F1: ark_ff::PrimeField, F2: ark_ff::PrimeField>(a_f1: F1) -> P
     let a_f1_control = F1::from_le_bytes_mod_order(&a_f2.into_bigint().to_bytes_le());
     (a_f1 == a_f1_control)
         .then_some(a_f2)
-        .ok_or(ProofError::SerializationError)
+        .ok_or(ProofError::SerializationError(None))
 }
 
 // pub trait PairingReader<P: ark_ec::pairing::Pairing>: DeserializeGroup<P::G1> + DeserializeGroup<P::G2>  {
@@ -1,20 +1,59 @@
+use alloc::vec::Vec;
+
 use ark_ec::CurveGroup;
 use ark_ff::{Field, Fp, FpConfig};
 use ark_serialize::CanonicalSerialize;
 use rand::{CryptoRng, RngCore};
 
-use super::{CommonFieldToUnit, CommonGroupToUnit, FieldToUnitSerialize, GroupToUnitSerialize};
+use super::{
+    CommonFieldToUnit, CommonGroupToUnit, FieldToPrivateCoins, FieldToUnitSerialize,
+    GroupToUnitSerialize,
+};
 use crate::{
-    BytesToUnitDeserialize, BytesToUnitSerialize, CommonUnitToBytes, DomainSeparatorMismatch,
-    DuplexSpongeInterface, ProofResult, ProverState, Unit, UnitTranscript, VerifierState,
+    io, BytesToUnitDeserialize, BytesToUnitSerialize, CommonUnitToBytes, DomainSeparatorMismatch,
+    DuplexSpongeInterface, NargSource, ProofResult, ProverState, Unit, UnitTranscript,
+    VerifierState,
 };
 
-impl<F: Field, H: DuplexSpongeInterface, R: RngCore + CryptoRng> FieldToUnitSerialize<F>
-    for ProverState<H, u8, R>
+impl<
+        F: Field,
+        H: DuplexSpongeInterface,
+        R: RngCore + CryptoRng,
+        P: DuplexSpongeInterface,
+        W: io::Write,
+    > FieldToUnitSerialize<F> for ProverState<H, u8, R, P, W>
 {
     fn add_scalars(&mut self, input: &[F]) -> ProofResult<()> {
         let serialized = self.public_scalars(input);
-        self.narg_string.extend(serialized?);
+        self.write_narg(&serialized?)?;
+        Ok(())
+    }
+
+    fn add_scalars_dyn(&mut self, input: &[F]) -> ProofResult<()> {
+        let mut buf = Vec::new();
+        for i in input {
+            i.serialize_compressed(&mut buf)?;
+        }
+        self.add_units_dyn(&buf)?;
+        Ok(())
+    }
+}
+
+impl<F, H, U, R, P, W> FieldToPrivateCoins<F> for ProverState<H, U, R, P, W>
+where
+    F: Field,
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+    R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
+{
+    fn absorb_secret_scalars(&mut self, input: &[F]) -> ProofResult<()> {
+        let mut buf = Vec::new();
+        for i in input {
+            i.serialize_compressed(&mut buf)?;
+        }
+        self.absorb_secret(&buf);
         Ok(())
     }
 }
@@ -23,66 +62,83 @@ impl<
         C: FpConfig<N>,
         H: DuplexSpongeInterface<Fp<C, N>>,
         R: RngCore + CryptoRng,
+        P: DuplexSpongeInterface,
+        W: io::Write,
         const N: usize,
-    > FieldToUnitSerialize<Fp<C, N>> for ProverState<H, Fp<C, N>, R>
+    > FieldToUnitSerialize<Fp<C, N>> for ProverState<H, Fp<C, N>, R, P, W>
 {
     fn add_scalars(&mut self, input: &[Fp<C, N>]) -> ProofResult<()> {
         self.public_units(input)?;
+        let mut buf = Vec::new();
         for i in input {
-            i.serialize_compressed(&mut self.narg_string)?;
+            i.serialize_compressed(&mut buf)?;
         }
+        self.write_narg(&buf)?;
+        Ok(())
+    }
+
+    fn add_scalars_dyn(&mut self, input: &[Fp<C, N>]) -> ProofResult<()> {
+        self.add_units_dyn(input)?;
         Ok(())
     }
 }
 
-impl<G, H, R> GroupToUnitSerialize<G> for ProverState<H, u8, R>
+impl<G, H, R, P, W> GroupToUnitSerialize<G> for ProverState<H, u8, R, P, W>
 where
     G: CurveGroup,
     H: DuplexSpongeInterface,
     R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
     Self: CommonGroupToUnit<G, Repr = Vec<u8>>,
 {
     fn add_points(&mut self, input: &[G]) -> ProofResult<()> {
         let serialized = self.public_points(input);
-        self.narg_string.extend(serialized?);
+        self.write_narg(&serialized?)?;
         Ok(())
     }
 }
 
-impl<G, H, R, C: FpConfig<N>, C2: FpConfig<N>, const N: usize> GroupToUnitSerialize<G>
-    for ProverState<H, Fp<C, N>, R>
+impl<G, H, R, P, W, C: FpConfig<N>, C2: FpConfig<N>, const N: usize> GroupToUnitSerialize<G>
+    for ProverState<H, Fp<C, N>, R, P, W>
 where
     G: CurveGroup<BaseField = Fp<C2, N>>,
     H: DuplexSpongeInterface<Fp<C, N>>,
     R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
     Self: CommonGroupToUnit<G> + FieldToUnitSerialize<G::BaseField>,
 {
     fn add_points(&mut self, input: &[G]) -> ProofResult<()> {
         self.public_points(input).map(|_| ())?;
+        let mut buf = Vec::new();
         for i in input {
-            i.serialize_compressed(&mut self.narg_string)?;
+            i.serialize_compressed(&mut buf)?;
         }
+        self.write_narg(&buf)?;
         Ok(())
     }
 }
 
-impl<H, R, C, const N: usize> BytesToUnitSerialize for ProverState<H, Fp<C, N>, R>
+impl<H, R, P, W, C, const N: usize> BytesToUnitSerialize for ProverState<H, Fp<C, N>, R, P, W>
 where
     H: DuplexSpongeInterface<Fp<C, N>>,
     C: FpConfig<N>,
     R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
 {
     fn add_bytes(&mut self, input: &[u8]) -> Result<(), DomainSeparatorMismatch> {
         self.public_bytes(input)?;
-        self.narg_string.extend(input);
-        Ok(())
+        self.write_narg(input)
     }
 }
 
-impl<H, C, const N: usize> BytesToUnitDeserialize for VerifierState<'_, H, Fp<C, N>>
+impl<H, C, const N: usize, R> BytesToUnitDeserialize for VerifierState<'_, H, Fp<C, N>, R>
 where
     H: DuplexSpongeInterface<Fp<C, N>>,
     C: FpConfig<N>,
+    R: NargSource,
 {
     fn fill_next_bytes(&mut self, input: &mut [u8]) -> Result<(), DomainSeparatorMismatch> {
         u8::read(&mut self.narg_string, input)?;
@@ -100,7 +156,7 @@ mod tests {
     use super::*;
     use crate::{
         codecs::arkworks_algebra::{
-            FieldDomainSeparator, FieldToUnitSerialize, GroupDomainSeparator,
+            FieldDomainSeparator, FieldToPrivateCoins, FieldToUnitSerialize, GroupDomainSeparator,
         },
         ByteDomainSeparator, DefaultHash, DomainSeparator,
     };
@@ -163,6 +219,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_absorb_secret_scalars_matches_absorb_secret() {
+        // Absorbing secret scalars is equivalent to absorbing their compressed encoding,
+        // and does not touch the protocol transcript.
+        let domsep = DomainSeparator::<DefaultHash>::new("test-secret");
+        let witness = Fr::rand(&mut ark_std::test_rng());
+        let mut encoded = Vec::new();
+        witness.serialize_compressed(&mut encoded).unwrap();
+
+        let mut prover_state = ProverState::new_deterministic(&domsep, b"");
+        prover_state.absorb_secret_scalars(&[witness]).unwrap();
+        let mut expected_state = ProverState::new_deterministic(&domsep, b"");
+        expected_state.absorb_secret(&encoded);
+
+        assert_eq!(
+            prover_state.rng().next_u64(),
+            expected_state.rng().next_u64()
+        );
+        assert!(prover_state.narg_string().is_empty());
+    }
+
     #[test]
     fn test_add_scalars_u8_unit() {
         // Construct a domain separator that absorbs 2 field elements
@@ -1,8 +1,11 @@
+use ark_bls12_381::Fr;
+use ark_ec::CurveGroup;
 use ark_ff::Field;
 
 use crate::{
-    ByteDomainSeparator, BytesToUnitDeserialize, BytesToUnitSerialize, DefaultHash,
-    DomainSeparator, DuplexSpongeInterface, ProofResult, Unit, UnitToBytes, UnitTranscript,
+    pattern::TranscriptType, ByteDomainSeparator, BytesToUnitDeserialize, BytesToUnitSerialize,
+    DefaultHash, DomainSeparator, DuplexSpongeInterface, ProofResult, Unit, UnitToBytes,
+    UnitTranscript,
 };
 
 /// Test that the algebraic hashes do use the IV generated from the domain separator.
@@ -38,6 +41,7 @@ where
         .challenge_bytes(16, "chal")
         .add_bytes(16, "resp")
         .challenge_scalars(2, "chal")
+        .add_scalars_dyn("dyn")
 }
 
 fn test_arkworks_end_to_end<F: Field, H: DuplexSpongeInterface>() -> ProofResult<()> {
@@ -64,12 +68,14 @@ fn test_arkworks_end_to_end<F: Field, H: DuplexSpongeInterface>() -> ProofResult
     prover_state.fill_challenge_bytes(&mut b0)?;
     prover_state.add_bytes(&b1)?;
     prover_state.fill_challenge_scalars(&mut f3)?;
+    prover_state.add_scalars_dyn(&[f0, f2])?;
 
     let mut verifier_state = domain_separator.to_verifier_state(prover_state.narg_string());
     let [g0, g1, g2]: [F; 3] = verifier_state.next_scalars()?;
     verifier_state.fill_challenge_bytes(&mut c0)?;
     let c1: [u8; 16] = verifier_state.next_bytes()?;
     verifier_state.fill_challenge_scalars(&mut g3)?;
+    let g4: Vec<F> = verifier_state.next_scalars_vec()?;
 
     assert_eq!(f0, g0);
     assert_eq!(f1, g1);
@@ -77,6 +83,7 @@ fn test_arkworks_end_to_end<F: Field, H: DuplexSpongeInterface>() -> ProofResult
     assert_eq!(f3, g3);
     assert_eq!(b0, c0);
     assert_eq!(b1, c1);
+    assert_eq!(g4, [f0, f2]);
 
     Ok(())
 }
@@ -103,3 +110,116 @@ fn test_arkworks() {
     test_arkworks_end_to_end::<F, DefaultHash>().unwrap();
     test_arkworks_end_to_end::<F2, DefaultHash>().unwrap();
 }
+
+fn ark_pattern<F: Field + TranscriptType, G: CurveGroup + TranscriptType>(
+) -> alloc::sync::Arc<crate::pattern::InteractionPattern> {
+    use crate::pattern::{Hierarchy, Interaction, Kind, Length, PatternState};
+
+    let mut pattern = PatternState::<u8>::new();
+    pattern.interact(Interaction::new::<G>(
+        Hierarchy::Atomic,
+        Kind::Message,
+        "com",
+        Length::Fixed(1),
+    ));
+    pattern.interact(Interaction::new::<F>(
+        Hierarchy::Atomic,
+        Kind::Challenge,
+        "chal",
+        Length::Fixed(2),
+    ));
+    pattern.interact(Interaction::new::<F>(
+        Hierarchy::Atomic,
+        Kind::Message,
+        "resp",
+        Length::Dynamic,
+    ));
+    alloc::sync::Arc::new(pattern.finalize())
+}
+
+#[test]
+fn test_arkworks_pattern_transcript() -> ProofResult<()> {
+    use ark_bls12_381::{Fr, G1Projective};
+    use ark_ec::PrimeGroup;
+
+    use super::{
+        FieldPatternProver, FieldPatternVerifier, GroupPatternProver, GroupPatternVerifier,
+    };
+    use crate::pattern::{PatternProver, PatternVerifier};
+
+    let pattern = ark_pattern::<Fr, G1Projective>();
+    let commitment = G1Projective::generator() * Fr::from(42);
+
+    let mut prover_state = PatternProver::<DefaultHash>::from(pattern.clone());
+    prover_state.add_points("com", &[commitment])?;
+    let [c0, c1]: [Fr; 2] = prover_state.challenge_scalars("chal")?;
+    prover_state.add_scalars_dyn("resp", &[c0 + c1, c0 * c1])?;
+    let narg_string = prover_state.finish()?;
+
+    let mut verifier_state = PatternVerifier::<DefaultHash>::new(pattern, &narg_string);
+    let [verifier_commitment]: [G1Projective; 1] = verifier_state.next_points("com")?;
+    let [d0, d1]: [Fr; 2] = verifier_state.challenge_scalars("chal")?;
+    let response: Vec<Fr> = verifier_state.next_scalars_vec("resp")?;
+    verifier_state.finish()?;
+
+    assert_eq!(verifier_commitment, commitment);
+    assert_eq!([d0, d1], [c0, c1]);
+    assert_eq!(response, [c0 + c1, c0 * c1]);
+    Ok(())
+}
+
+#[test]
+fn test_arkworks_pattern_rejects_other_field() {
+    use ark_bls12_381::{Fq, Fr, G1Projective};
+    use ark_ec::PrimeGroup;
+
+    use super::{FieldPatternProver, GroupPatternProver};
+    use crate::pattern::PatternProver;
+
+    let mut prover_state = PatternProver::<DefaultHash>::from(ark_pattern::<Fr, G1Projective>());
+    prover_state
+        .add_points("com", &[G1Projective::generator()])
+        .unwrap();
+    // challenges in the base field instead of the scalar field
+    let result: ProofResult<[Fq; 2]> = prover_state.challenge_scalars("chal");
+    let err = result.unwrap_err().to_string();
+    assert!(
+        err.contains("but expected Atomic Challenge chal Fixed(2)"),
+        "{err}"
+    );
+}
+
+/// A transcript written against the original field element traits.
+struct LegacyTranscript;
+
+impl super::CommonFieldToUnit<Fr> for LegacyTranscript {
+    type Repr = ();
+
+    fn public_scalars(&mut self, _input: &[Fr]) -> ProofResult<()> {
+        Ok(())
+    }
+}
+
+impl super::FieldToUnitSerialize<Fr> for LegacyTranscript {
+    fn add_scalars(&mut self, _input: &[Fr]) -> ProofResult<()> {
+        Ok(())
+    }
+}
+
+impl super::FieldToUnitDeserialize<Fr> for LegacyTranscript {
+    fn fill_next_scalars(&mut self, _output: &mut [Fr]) -> ProofResult<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_field_transcript_provided_methods() {
+    use super::{FieldToUnitDeserialize, FieldToUnitSerialize};
+
+    let scalars = [Fr::from(1)];
+    assert!(LegacyTranscript.add_scalars_dyn(&scalars).is_err());
+    assert!(FieldToUnitDeserialize::<Fr>::next_scalars_vec(&mut LegacyTranscript).is_err());
+    assert!(
+        FieldToUnitDeserialize::<Fr>::next_scalars_vec_bounded(&mut LegacyTranscript, 1).is_err()
+    );
+}
@@ -0,0 +1,141 @@
+use alloc::{borrow::Cow, format};
+
+use ark_ec::{short_weierstrass, twisted_edwards};
+use ark_ff::{
+    BigInteger, CubicExtConfig, CubicExtField, Fp, FpConfig, PrimeField, QuadExtConfig,
+    QuadExtField,
+};
+
+use crate::pattern::TranscriptType;
+
+/// Prime fields are identified by their modulus, in big-endian hexadecimal.
+impl<P: FpConfig<N>, const N: usize> TranscriptType for Fp<P, N> {
+    fn type_id() -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "Fp(0x{})",
+            hex::encode(Self::MODULUS.to_bytes_be())
+        ))
+    }
+}
+
+/// Quadratic extensions are identified by their base field and non-residue.
+impl<P: QuadExtConfig> TranscriptType for QuadExtField<P>
+where
+    P::BaseField: TranscriptType,
+{
+    fn type_id() -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "QuadExt({}, {})",
+            P::BaseField::type_id(),
+            P::NONRESIDUE
+        ))
+    }
+}
+
+/// Cubic extensions are identified by their base field and non-residue.
+impl<P: CubicExtConfig> TranscriptType for CubicExtField<P>
+where
+    P::BaseField: TranscriptType,
+{
+    fn type_id() -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "CubicExt({}, {})",
+            P::BaseField::type_id(),
+            P::NONRESIDUE
+        ))
+    }
+}
+
+/// Short Weierstrass curves are identified by their base field, coefficients and generator.
+///
+/// Projective and affine points share the identifier, as they share the encoding.
+fn short_weierstrass_id<P: short_weierstrass::SWCurveConfig>() -> Cow<'static, str>
+where
+    P::BaseField: TranscriptType,
+{
+    Cow::Owned(format!(
+        "SW({}, {}, {}, {})",
+        P::BaseField::type_id(),
+        P::COEFF_A,
+        P::COEFF_B,
+        P::GENERATOR
+    ))
+}
+
+impl<P: short_weierstrass::SWCurveConfig> TranscriptType for short_weierstrass::Projective<P>
+where
+    P::BaseField: TranscriptType,
+{
+    fn type_id() -> Cow<'static, str> {
+        short_weierstrass_id::<P>()
+    }
+}
+
+impl<P: short_weierstrass::SWCurveConfig> TranscriptType for short_weierstrass::Affine<P>
+where
+    P::BaseField: TranscriptType,
+{
+    fn type_id() -> Cow<'static, str> {
+        short_weierstrass_id::<P>()
+    }
+}
+
+/// Twisted Edwards curves are identified by their base field, coefficients and generator.
+fn twisted_edwards_id<P: twisted_edwards::TECurveConfig>() -> Cow<'static, str>
+where
+    P::BaseField: TranscriptType,
+{
+    Cow::Owned(format!(
+        "TE({}, {}, {}, {})",
+        P::BaseField::type_id(),
+        P::COEFF_A,
+        P::COEFF_D,
+        P::GENERATOR
+    ))
+}
+
+impl<P: twisted_edwards::TECurveConfig> TranscriptType for twisted_edwards::Projective<P>
+where
+    P::BaseField: TranscriptType,
+{
+    fn type_id() -> Cow<'static, str> {
+        twisted_edwards_id::<P>()
+    }
+}
+
+impl<P: twisted_edwards::TECurveConfig> TranscriptType for twisted_edwards::Affine<P>
+where
+    P::BaseField: TranscriptType,
+{
+    fn type_id() -> Cow<'static, str> {
+        twisted_edwards_id::<P>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Fq, Fq12, Fr, G1Affine, G1Projective, G2Projective};
+
+    use super::*;
+
+    #[test]
+    fn test_type_ids() {
+        assert_eq!(
+            Fr::type_id(),
+            "Fp(0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001)"
+        );
+        assert!(Fq12::type_id().starts_with("QuadExt(CubicExt(QuadExt(Fp(0x1a0111ea"));
+        assert_eq!(G1Projective::type_id(), G1Affine::type_id());
+
+        let ids = [
+            Fr::type_id(),
+            Fq::type_id(),
+            Fq12::type_id(),
+            G1Projective::type_id(),
+            G2Projective::type_id(),
+        ];
+        for (i, id) in ids.iter().enumerate() {
+            assert!(ids[..i].iter().all(|other| other != id), "{id}");
+        }
+    }
+}
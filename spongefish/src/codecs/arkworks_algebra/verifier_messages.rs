@@ -1,4 +1,4 @@
-use std::io;
+use alloc::{vec, vec::Vec};
 
 use ark_ec::{AffineRepr, CurveGroup};
 use ark_ff::{BigInteger, Field, Fp, FpConfig, PrimeField};
@@ -7,33 +7,62 @@ use rand::{CryptoRng, RngCore};
 
 use super::{CommonFieldToUnit, CommonGroupToUnit, UnitToField};
 use crate::{
-    codecs::bytes_uniform_modp, CommonUnitToBytes, DomainSeparatorMismatch, DuplexSpongeInterface,
-    ProofError, ProofResult, ProverState, Unit, UnitToBytes, UnitTranscript, VerifierState,
+    codecs::bytes_uniform_modp, io, CommonUnitToBytes, DomainSeparatorMismatch,
+    DuplexSpongeInterface, NargSource, ProofError, ProofResult, ProverState, Unit, UnitToBytes,
+    UnitTranscript, VerifierState,
 };
 
 // Implementation of basic traits for bridging arkworks and spongefish
 
+// Elements go through a byte buffer, since arkworks' own I/O traits
+// only coincide with [`crate::io`] when `std` is enabled.
 impl<C: FpConfig<N>, const N: usize> Unit for Fp<C, N> {
-    fn write(bunch: &[Self], mut w: &mut impl io::Write) -> Result<(), io::Error> {
+    fn write(bunch: &[Self], w: &mut impl io::Write) -> Result<(), io::Error> {
+        let mut buf = Vec::with_capacity(bunch.len() * Self::encoded_size());
         for b in bunch {
-            b.serialize_compressed(&mut w)
-                .map_err(|_| io::Error::other("oh no!"))?;
+            b.serialize_compressed(&mut buf).map_err(io_error)?;
         }
-        Ok(())
+        w.write_all(&buf)
     }
 
-    fn read(mut r: &mut impl io::Read, bunch: &mut [Self]) -> Result<(), io::Error> {
+    fn read(r: &mut impl io::Read, bunch: &mut [Self]) -> Result<(), io::Error> {
+        let mut buf = vec![0u8; Self::encoded_size()];
         for b in bunch.iter_mut() {
-            let b_result = Self::deserialize_compressed(&mut r);
-            *b = b_result.map_err(|_| io::Error::other("Unable to deserialize into Field."))?;
+            r.read_exact(&mut buf)?;
+            *b = Self::deserialize_compressed(buf.as_slice()).map_err(io_error)?;
         }
         Ok(())
     }
+
+    fn encoded_size() -> usize {
+        Self::default().compressed_size()
+    }
+}
+
+/// Wrap an arkworks error into an I/O error.
+///
+/// Arkworks errors implement [`core::error::Error`] only with `std`:
+/// without it, only their message is kept.
+#[cfg(feature = "std")]
+fn io_error(err: SerializationError) -> io::Error {
+    io::Error::other(err)
+}
+
+#[cfg(not(feature = "std"))]
+#[allow(clippy::needless_pass_by_value)] // Same signature as with `std`
+fn io_error(err: SerializationError) -> io::Error {
+    io::Error::other(alloc::string::ToString::to_string(&err))
 }
 
 impl From<SerializationError> for ProofError {
-    fn from(_value: SerializationError) -> Self {
-        Self::SerializationError
+    #[cfg(feature = "std")]
+    fn from(value: SerializationError) -> Self {
+        Self::serialization(value)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn from(value: SerializationError) -> Self {
+        Self::serialization(io_error(value))
     }
 }
 
@@ -94,10 +123,11 @@ where
     }
 }
 
-impl<H, C, const N: usize> UnitToField<Fp<C, N>> for VerifierState<'_, H, Fp<C, N>>
+impl<H, C, const N: usize, R> UnitToField<Fp<C, N>> for VerifierState<'_, H, Fp<C, N>, R>
 where
     C: FpConfig<N>,
     H: DuplexSpongeInterface<Fp<C, N>>,
+    R: NargSource,
 {
     fn fill_challenge_scalars(&mut self, output: &mut [Fp<C, N>]) -> ProofResult<()> {
         self.fill_challenge_units(output)
@@ -105,11 +135,13 @@ where
     }
 }
 
-impl<H, C, R, const N: usize> UnitToField<Fp<C, N>> for ProverState<H, Fp<C, N>, R>
+impl<H, C, R, P, W, const N: usize> UnitToField<Fp<C, N>> for ProverState<H, Fp<C, N>, R, P, W>
 where
     C: FpConfig<N>,
     H: DuplexSpongeInterface<Fp<C, N>>,
     R: CryptoRng + RngCore,
+    P: DuplexSpongeInterface,
+    W: io::Write,
 {
     fn fill_challenge_scalars(&mut self, output: &mut [Fp<C, N>]) -> ProofResult<()> {
         self.fill_challenge_units(output)
@@ -119,11 +151,13 @@ where
 
 // Field <-> Field interactions:
 
-impl<F, H, R, C, const N: usize> CommonFieldToUnit<F> for ProverState<H, Fp<C, N>, R>
+impl<F, H, R, P, W, C, const N: usize> CommonFieldToUnit<F> for ProverState<H, Fp<C, N>, R, P, W>
 where
     F: Field<BasePrimeField = Fp<C, N>>,
     H: DuplexSpongeInterface<Fp<C, N>>,
     R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
     C: FpConfig<N>,
 {
     type Repr = ();
@@ -157,11 +191,12 @@ where
 //
 //
 
-impl<F, H, C, const N: usize> CommonFieldToUnit<F> for VerifierState<'_, H, Fp<C, N>>
+impl<F, H, C, const N: usize, R> CommonFieldToUnit<F> for VerifierState<'_, H, Fp<C, N>, R>
 where
     F: Field<BasePrimeField = Fp<C, N>>,
     H: DuplexSpongeInterface<Fp<C, N>>,
     C: FpConfig<N>,
+    R: NargSource,
 {
     type Repr = ();
 
@@ -175,10 +210,12 @@ where
     }
 }
 
-impl<H, R, C, const N: usize, G> CommonGroupToUnit<G> for ProverState<H, Fp<C, N>, R>
+impl<H, R, P, W, C, const N: usize, G> CommonGroupToUnit<G> for ProverState<H, Fp<C, N>, R, P, W>
 where
     C: FpConfig<N>,
     R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
     H: DuplexSpongeInterface<Fp<C, N>>,
     G: CurveGroup<BaseField = Fp<C, N>>,
 {
@@ -193,11 +230,12 @@ where
     }
 }
 
-impl<H, C, const N: usize, G> CommonGroupToUnit<G> for VerifierState<'_, H, Fp<C, N>>
+impl<H, C, const N: usize, G, R> CommonGroupToUnit<G> for VerifierState<'_, H, Fp<C, N>, R>
 where
     C: FpConfig<N>,
     H: DuplexSpongeInterface<Fp<C, N>>,
     G: CurveGroup<BaseField = Fp<C, N>>,
+    R: NargSource,
 {
     type Repr = ();
 
@@ -212,10 +250,11 @@ where
 
 // Field  <-> Bytes interactions:
 
-impl<H, C, const N: usize> CommonUnitToBytes for VerifierState<'_, H, Fp<C, N>>
+impl<H, C, const N: usize, R> CommonUnitToBytes for VerifierState<'_, H, Fp<C, N>, R>
 where
     C: FpConfig<N>,
     H: DuplexSpongeInterface<Fp<C, N>>,
+    R: NargSource,
 {
     fn public_bytes(&mut self, input: &[u8]) -> Result<(), DomainSeparatorMismatch> {
         for &byte in input {
@@ -225,11 +264,13 @@ where
     }
 }
 
-impl<H, R, C, const N: usize> CommonUnitToBytes for ProverState<H, Fp<C, N>, R>
+impl<H, R, P, W, C, const N: usize> CommonUnitToBytes for ProverState<H, Fp<C, N>, R, P, W>
 where
     C: FpConfig<N>,
     H: DuplexSpongeInterface<Fp<C, N>>,
     R: CryptoRng + rand::RngCore,
+    P: DuplexSpongeInterface,
+    W: io::Write,
 {
     fn public_bytes(&mut self, input: &[u8]) -> Result<(), DomainSeparatorMismatch> {
         for &byte in input {
@@ -239,11 +280,13 @@ where
     }
 }
 
-impl<H, R, C, const N: usize> UnitToBytes for ProverState<H, Fp<C, N>, R>
+impl<H, R, P, W, C, const N: usize> UnitToBytes for ProverState<H, Fp<C, N>, R, P, W>
 where
     C: FpConfig<N>,
     H: DuplexSpongeInterface<Fp<C, N>>,
     R: CryptoRng + RngCore,
+    P: DuplexSpongeInterface,
+    W: io::Write,
 {
     fn fill_challenge_bytes(&mut self, output: &mut [u8]) -> Result<(), DomainSeparatorMismatch> {
         if output.is_empty() {
@@ -265,10 +308,11 @@ where
 }
 
 /// XXX. duplicate code
-impl<H, C, const N: usize> UnitToBytes for VerifierState<'_, H, Fp<C, N>>
+impl<H, C, const N: usize, R> UnitToBytes for VerifierState<'_, H, Fp<C, N>, R>
 where
     C: FpConfig<N>,
     H: DuplexSpongeInterface<Fp<C, N>>,
+    R: NargSource,
 {
     fn fill_challenge_bytes(&mut self, output: &mut [u8]) -> Result<(), DomainSeparatorMismatch> {
         if output.is_empty() {
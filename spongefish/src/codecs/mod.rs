@@ -54,6 +54,17 @@ pub(super) const fn bytes_modp(modulus_bits: u32) -> usize {
     (modulus_bits as usize).div_ceil(8)
 }
 
+/// Compute `count * size`, failing instead of overflowing.
+#[cfg(any(feature = "arkworks-algebra", feature = "zkcrypto-group"))]
+pub(super) fn checked_count(
+    count: usize,
+    size: usize,
+) -> Result<usize, crate::DomainSeparatorMismatch> {
+    count
+        .checked_mul(size)
+        .ok_or_else(|| "Count overflows usize.".into())
+}
+
 /// Unit-tests for inter-operability among libraries.
 #[cfg(all(test, feature = "arkworks-algebra", feature = "zkcrypto-group"))]
 mod tests;
@@ -67,6 +67,98 @@ fn test_compatible_pasta() {
     // compatible_groups::<ArkG, GroupG>();
 }
 
+/// Hints sent with arkworks can be read with group, and vice versa.
+#[test]
+fn test_compatible_hints_bls12_381() {
+    use ark_ec::PrimeGroup;
+    use codecs::{arkworks_algebra as ark, zkcrypto_group as zk};
+
+    type ArkG = ark_bls12_381::G1Projective;
+    type GroupG = bls12_381::G1Projective;
+
+    let domsep = DomainSeparator::<Keccak>::new("hints")
+        .hint_bounded(64, "scalars")
+        .hint_bounded(48, "point");
+    let ark_scalars = [ark_bls12_381::Fr::from(0x42), ark_bls12_381::Fr::from(7)];
+    let ark_point = ArkG::generator() * ark_scalars[0];
+    let group_scalars = [bls12_381::Scalar::from(0x42), bls12_381::Scalar::from(7)];
+    let group_point = GroupG::generator() * group_scalars[0];
+
+    let mut prover_state = domsep.to_prover_state();
+    ark::HintSerialize::hint_scalars(&mut prover_state, &ark_scalars).unwrap();
+    ark::HintSerialize::hint_points(&mut prover_state, &[ark_point]).unwrap();
+    let ark_narg_string = prover_state.finish().unwrap();
+
+    let mut prover_state = domsep.to_prover_state();
+    zk::HintSerialize::hint_scalars(&mut prover_state, &group_scalars).unwrap();
+    zk::HintSerialize::hint_points(&mut prover_state, &[group_point]).unwrap();
+    let group_narg_string = prover_state.finish().unwrap();
+    assert_eq!(ark_narg_string, group_narg_string);
+
+    let mut verifier_state = domsep.to_verifier_state(&ark_narg_string);
+    let scalars: Vec<bls12_381::Scalar> =
+        zk::HintDeserialize::hint_scalars(&mut verifier_state).unwrap();
+    let points: Vec<GroupG> = zk::HintDeserialize::hint_points(&mut verifier_state).unwrap();
+    assert_eq!(scalars, group_scalars);
+    assert_eq!(points, [group_point]);
+
+    // A point encoding with the infinity flag and a non-zero coordinate is rejected.
+    let mut forged = ark_narg_string;
+    let point_start = forged.len() - 48;
+    forged[point_start] |= 0x40;
+    let mut verifier_state = domsep.to_verifier_state(&forged);
+    zk::HintDeserialize::hint_scalars::<bls12_381::Scalar>(&mut verifier_state).unwrap();
+    assert!(zk::HintDeserialize::hint_points::<GroupG>(&mut verifier_state).is_err());
+}
+
+#[test]
+fn test_compatible_dynamic_scalars_bls12_381() {
+    use codecs::{arkworks_algebra as ark, zkcrypto_group as zk};
+
+    let domsep = ark::FieldDomainSeparator::<ark_bls12_381::Fr>::add_scalars_dyn(
+        DomainSeparator::<Keccak>::new("dynamic"),
+        "scalars",
+    )
+    .challenge_bytes(16, "chal");
+    let ark_scalars = [ark_bls12_381::Fr::from(0x42), ark_bls12_381::Fr::from(7)];
+    let group_scalars = [bls12_381::Scalar::from(0x42), bls12_381::Scalar::from(7)];
+
+    let mut prover_state = domsep.to_prover_state();
+    ark::FieldToUnitSerialize::add_scalars_dyn(&mut prover_state, &ark_scalars).unwrap();
+    let ark_chal = prover_state.challenge_bytes::<16>().unwrap();
+    let ark_narg_string = prover_state.finish().unwrap();
+
+    let mut prover_state = domsep.to_prover_state();
+    zk::FieldToUnitSerialize::add_scalars_dyn(&mut prover_state, &group_scalars).unwrap();
+    let group_chal = prover_state.challenge_bytes::<16>().unwrap();
+    assert_eq!(ark_chal, group_chal);
+    assert_eq!(ark_narg_string, prover_state.finish().unwrap());
+    assert_eq!(ark_narg_string[..4], 64u32.to_le_bytes());
+
+    let mut verifier_state = domsep.to_verifier_state(&ark_narg_string);
+    let scalars: Vec<bls12_381::Scalar> =
+        zk::FieldToUnitDeserialize::next_scalars_vec(&mut verifier_state).unwrap();
+    assert_eq!(scalars, group_scalars);
+    assert_eq!(verifier_state.challenge_bytes::<16>().unwrap(), ark_chal);
+
+    // A length that is not a multiple of the element size is rejected.
+    let mut forged = ark_narg_string.clone();
+    forged[..4].copy_from_slice(&63u32.to_le_bytes());
+    let mut verifier_state = domsep.to_verifier_state(&forged);
+    assert!(
+        ark::FieldToUnitDeserialize::<ark_bls12_381::Fr>::next_scalars_vec(&mut verifier_state)
+            .is_err()
+    );
+
+    // A length beyond the NARG string is rejected without allocating it.
+    forged[..4].copy_from_slice(&u32::MAX.to_le_bytes().map(|b| b & 0xe0));
+    let mut verifier_state = domsep.to_verifier_state(&forged);
+    assert!(
+        zk::FieldToUnitDeserialize::<bls12_381::Scalar>::next_scalars_vec(&mut verifier_state)
+            .is_err()
+    );
+}
+
 // Check that the transcripts generated using the Group trait can be compatible with transcripts generated using group.
 fn compatible_groups<ArkG, GroupG>()
 where
@@ -1,11 +1,67 @@
 macro_rules! field_traits {
     ($Field:path) => {
+        super::traits::field_traits!($Field, Family: ());
+    };
+    ($Field:path, Family: $Family:ty) => {
         /// Absorb and squeeze field elements to the domain separator.
-        pub trait FieldDomainSeparator<F: $Field> {
+        ///
+        /// The `try_` methods return an error on invalid counts or labels,
+        /// while the others panic.
+        /// Their default implementations report the operation as unsupported:
+        /// implementations should override them.
+        pub trait FieldDomainSeparator<F: $Field>: Sized {
             #[must_use]
             fn add_scalars(self, count: usize, label: &str) -> Self;
             #[must_use]
             fn challenge_scalars(self, count: usize, label: &str) -> Self;
+
+            fn try_add_scalars(
+                self,
+                _count: usize,
+                _label: &str,
+            ) -> Result<Self, $crate::DomainSeparatorMismatch> {
+                Err("Checked absorption of field elements is not supported by this domain separator.".into())
+            }
+            fn try_challenge_scalars(
+                self,
+                _count: usize,
+                _label: &str,
+            ) -> Result<Self, $crate::DomainSeparatorMismatch> {
+                Err("Checked field element challenges are not supported by this domain separator.".into())
+            }
+            /// Hint at most `count` field elements.
+            fn try_hint_scalars(
+                self,
+                _count: usize,
+                _label: &str,
+            ) -> Result<Self, $crate::DomainSeparatorMismatch> {
+                Err("Hinting field elements is not supported by this domain separator.".into())
+            }
+            /// Absorb a variable number of field elements, prefixed by their length.
+            fn try_add_scalars_dyn(
+                self,
+                _label: &str,
+            ) -> Result<Self, $crate::DomainSeparatorMismatch> {
+                Err("Variable-length field elements are not supported by this domain separator.".into())
+            }
+
+            #[must_use]
+            fn hint_scalars(self, count: usize, label: &str) -> Self {
+                self.try_hint_scalars(count, label)
+                    .unwrap_or_else(|e| panic!("{e}"))
+            }
+            #[must_use]
+            fn add_scalars_dyn(self, label: &str) -> Self {
+                self.try_add_scalars_dyn(label)
+                    .unwrap_or_else(|e| panic!("{e}"))
+            }
+        }
+
+        /// Absorb secret field elements (e.g., the witness) in the prover's private coins.
+        ///
+        /// The field elements are neither absorbed in the public sponge nor written to the protocol transcript.
+        pub trait FieldToPrivateCoins<F: $Field> {
+            fn absorb_secret_scalars(&mut self, input: &[F]) -> $crate::ProofResult<()>;
         }
 
         /// Interpret verifier messages as uniformly distributed field elements.
@@ -31,6 +87,16 @@ macro_rules! field_traits {
         /// Add field elements to the protocol transcript.
         pub trait FieldToUnitSerialize<F: $Field>: CommonFieldToUnit<F> {
             fn add_scalars(&mut self, input: &[F]) -> crate::ProofResult<()>;
+
+            /// Add a variable number of field elements, prefixed by their length.
+            ///
+            /// The default implementation reports variable-length messages as unsupported.
+            fn add_scalars_dyn(&mut self, _input: &[F]) -> crate::ProofResult<()> {
+                Err($crate::DomainSeparatorMismatch::from(
+                    "Variable-length field elements are not supported by this prover state.",
+                )
+                .into())
+            }
         }
 
         /// Deserialize field elements from the protocol transcript.
@@ -40,22 +106,262 @@ macro_rules! field_traits {
         pub trait FieldToUnitDeserialize<F: $Field>: CommonFieldToUnit<F> {
             fn fill_next_scalars(&mut self, output: &mut [F]) -> crate::ProofResult<()>;
 
+            /// Read a variable number of field elements, sent with [`FieldToUnitSerialize::add_scalars_dyn`].
+            ///
+            /// The length prefix is untrusted: elements are decoded as they are read.
+            fn next_scalars_vec(&mut self) -> crate::ProofResult<alloc::vec::Vec<F>> {
+                self.next_scalars_vec_bounded(usize::MAX)
+            }
+
+            /// Read a variable number of field elements, as [`Self::next_scalars_vec`],
+            /// rejecting a length prefix of more than `max_len` elements before reading them.
+            ///
+            /// The default implementation reports variable-length messages as unsupported.
+            fn next_scalars_vec_bounded(
+                &mut self,
+                _max_len: usize,
+            ) -> crate::ProofResult<alloc::vec::Vec<F>> {
+                Err($crate::DomainSeparatorMismatch::from(
+                    "Variable-length field elements are not supported by this verifier state.",
+                )
+                .into())
+            }
+
             fn next_scalars<const N: usize>(&mut self) -> crate::ProofResult<[F; N]> {
                 let mut output = [F::default(); N];
                 self.fill_next_scalars(&mut output)?;
                 Ok(output)
             }
         }
+
+        /// Send field elements in a transcript following an interaction pattern
+        /// (see [`PatternTranscript`]($crate::pattern::PatternTranscript)).
+        ///
+        /// Each call is checked against the pattern, including the type of the field elements.
+        pub trait FieldPatternProver<F: $Field> {
+            fn add_scalars(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                input: &[F],
+            ) -> $crate::ProofResult<()>;
+
+            fn add_scalars_dyn(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                input: &[F],
+            ) -> $crate::ProofResult<()>;
+
+            fn public_scalars(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                input: &[F],
+            ) -> $crate::ProofResult<()>;
+
+            fn fill_challenge_scalars(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                output: &mut [F],
+            ) -> $crate::ProofResult<()>;
+
+            fn challenge_scalars<const N: usize>(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+            ) -> $crate::ProofResult<[F; N]> {
+                let mut output = [F::default(); N];
+                self.fill_challenge_scalars(label, &mut output)?;
+                Ok(output)
+            }
+        }
+
+        /// Receive field elements in a transcript following an interaction pattern
+        /// (see [`PatternTranscript`]($crate::pattern::PatternTranscript)).
+        ///
+        /// Each call is checked against the pattern, including the type of the field elements.
+        pub trait FieldPatternVerifier<F: $Field> {
+            fn fill_next_scalars(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                output: &mut [F],
+            ) -> $crate::ProofResult<()>;
+
+            fn next_scalars<const N: usize>(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+            ) -> $crate::ProofResult<[F; N]> {
+                let mut output = [F::default(); N];
+                self.fill_next_scalars(label, &mut output)?;
+                Ok(output)
+            }
+
+            fn next_scalars_vec(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+            ) -> $crate::ProofResult<alloc::vec::Vec<F>>;
+
+            fn public_scalars(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                input: &[F],
+            ) -> $crate::ProofResult<()>;
+
+            fn fill_challenge_scalars(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                output: &mut [F],
+            ) -> $crate::ProofResult<()>;
+
+            fn challenge_scalars<const N: usize>(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+            ) -> $crate::ProofResult<[F; N]> {
+                let mut output = [F::default(); N];
+                self.fill_challenge_scalars(label, &mut output)?;
+                Ok(output)
+            }
+        }
+
+        impl<T, F> FieldPatternProver<F> for T
+        where
+            F: $Field + $crate::pattern::TranscriptType<$Family>,
+            T: $crate::pattern::PatternTranscript,
+            T::State: FieldToUnitSerialize<F> + UnitToField<F>,
+        {
+            fn add_scalars(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                input: &[F],
+            ) -> $crate::ProofResult<()> {
+                let length = $crate::pattern::Length::Fixed(input.len());
+                self.interact::<F, $Family, _, _>($crate::pattern::Kind::Message, label, length, |state| {
+                    FieldToUnitSerialize::add_scalars(state, input)
+                })
+            }
+
+            fn add_scalars_dyn(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                input: &[F],
+            ) -> $crate::ProofResult<()> {
+                let length = $crate::pattern::Length::Runtime(input.len());
+                self.interact::<F, $Family, _, _>($crate::pattern::Kind::Message, label, length, |state| {
+                    FieldToUnitSerialize::add_scalars_dyn(state, input)
+                })
+            }
+
+            fn public_scalars(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                input: &[F],
+            ) -> $crate::ProofResult<()> {
+                let length = $crate::pattern::Length::Fixed(input.len());
+                self.interact::<F, $Family, _, _>($crate::pattern::Kind::Public, label, length, |state| {
+                    CommonFieldToUnit::public_scalars(state, input).map(|_| ())
+                })
+            }
+
+            fn fill_challenge_scalars(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                output: &mut [F],
+            ) -> $crate::ProofResult<()> {
+                let length = $crate::pattern::Length::Fixed(output.len());
+                self.interact::<F, $Family, _, _>($crate::pattern::Kind::Challenge, label, length, |state| {
+                    UnitToField::fill_challenge_scalars(state, output)
+                })
+            }
+        }
+
+        impl<T, F> FieldPatternVerifier<F> for T
+        where
+            F: $Field + $crate::pattern::TranscriptType<$Family>,
+            T: $crate::pattern::PatternTranscript,
+            T::State: FieldToUnitDeserialize<F> + UnitToField<F>,
+        {
+            fn fill_next_scalars(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                output: &mut [F],
+            ) -> $crate::ProofResult<()> {
+                let length = $crate::pattern::Length::Fixed(output.len());
+                self.interact::<F, $Family, _, _>($crate::pattern::Kind::Message, label, length, |state| {
+                    FieldToUnitDeserialize::fill_next_scalars(state, output)
+                })
+            }
+
+            fn next_scalars_vec(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+            ) -> $crate::ProofResult<alloc::vec::Vec<F>> {
+                self.interact_dyn::<F, $Family, _, _>(
+                    $crate::pattern::Kind::Message,
+                    label,
+                    FieldToUnitDeserialize::next_scalars_vec_bounded,
+                    alloc::vec::Vec::len,
+                )
+            }
+
+            fn public_scalars(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                input: &[F],
+            ) -> $crate::ProofResult<()> {
+                let length = $crate::pattern::Length::Fixed(input.len());
+                self.interact::<F, $Family, _, _>($crate::pattern::Kind::Public, label, length, |state| {
+                    CommonFieldToUnit::public_scalars(state, input).map(|_| ())
+                })
+            }
+
+            fn fill_challenge_scalars(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                output: &mut [F],
+            ) -> $crate::ProofResult<()> {
+                let length = $crate::pattern::Length::Fixed(output.len());
+                self.interact::<F, $Family, _, _>($crate::pattern::Kind::Challenge, label, length, |state| {
+                    UnitToField::fill_challenge_scalars(state, output)
+                })
+            }
+        }
     };
 }
 
 #[macro_export]
 macro_rules! group_traits {
     ($Group:path, Scalar: $Field:path) => {
+        $crate::group_traits!($Group, Scalar: $Field, Family: ());
+    };
+    ($Group:path, Scalar: $Field:path, Family: $Family:ty) => {
         /// Send group elements in the domain separator.
-        pub trait GroupDomainSeparator<G: $Group> {
+        ///
+        /// The `try_` methods return an error on invalid counts or labels,
+        /// while the others panic.
+        /// Their default implementations report the operation as unsupported:
+        /// implementations should override them.
+        pub trait GroupDomainSeparator<G: $Group>: Sized {
             #[must_use]
             fn add_points(self, count: usize, label: &str) -> Self;
+
+            fn try_add_points(
+                self,
+                _count: usize,
+                _label: &str,
+            ) -> Result<Self, $crate::DomainSeparatorMismatch> {
+                Err("Checked absorption of group elements is not supported by this domain separator.".into())
+            }
+            /// Hint at most `count` group elements.
+            fn try_hint_points(
+                self,
+                _count: usize,
+                _label: &str,
+            ) -> Result<Self, $crate::DomainSeparatorMismatch> {
+                Err("Hinting group elements is not supported by this domain separator.".into())
+            }
+
+            #[must_use]
+            fn hint_points(self, count: usize, label: &str) -> Self {
+                self.try_hint_points(count, label)
+                    .unwrap_or_else(|e| panic!("{e}"))
+            }
         }
 
         /// Adds a new prover message consisting of an EC element.
@@ -89,6 +395,109 @@ macro_rules! group_traits {
             /// Incorporate group elements into the proof without adding them to the final protocol transcript.
             fn public_points(&mut self, input: &[G]) -> $crate::ProofResult<Self::Repr>;
         }
+
+        /// Send group elements in a transcript following an interaction pattern
+        /// (see [`PatternTranscript`]($crate::pattern::PatternTranscript)).
+        ///
+        /// Each call is checked against the pattern, including the type of the group elements.
+        pub trait GroupPatternProver<G: $Group> {
+            fn add_points(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                input: &[G],
+            ) -> $crate::ProofResult<()>;
+
+            fn public_points(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                input: &[G],
+            ) -> $crate::ProofResult<()>;
+        }
+
+        /// Receive group elements in a transcript following an interaction pattern
+        /// (see [`PatternTranscript`]($crate::pattern::PatternTranscript)).
+        ///
+        /// Each call is checked against the pattern, including the type of the group elements.
+        pub trait GroupPatternVerifier<G: $Group + Default> {
+            fn fill_next_points(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                output: &mut [G],
+            ) -> $crate::ProofResult<()>;
+
+            fn next_points<const N: usize>(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+            ) -> $crate::ProofResult<[G; N]> {
+                let mut output = [G::default(); N];
+                self.fill_next_points(label, &mut output)?;
+                Ok(output)
+            }
+
+            fn public_points(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                input: &[G],
+            ) -> $crate::ProofResult<()>;
+        }
+
+        impl<T, G> GroupPatternProver<G> for T
+        where
+            G: $Group + $crate::pattern::TranscriptType<$Family>,
+            T: $crate::pattern::PatternTranscript,
+            T::State: GroupToUnitSerialize<G>,
+        {
+            fn add_points(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                input: &[G],
+            ) -> $crate::ProofResult<()> {
+                let length = $crate::pattern::Length::Fixed(input.len());
+                self.interact::<G, $Family, _, _>($crate::pattern::Kind::Message, label, length, |state| {
+                    GroupToUnitSerialize::add_points(state, input)
+                })
+            }
+
+            fn public_points(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                input: &[G],
+            ) -> $crate::ProofResult<()> {
+                let length = $crate::pattern::Length::Fixed(input.len());
+                self.interact::<G, $Family, _, _>($crate::pattern::Kind::Public, label, length, |state| {
+                    CommonGroupToUnit::public_points(state, input).map(|_| ())
+                })
+            }
+        }
+
+        impl<T, G> GroupPatternVerifier<G> for T
+        where
+            G: $Group + Default + $crate::pattern::TranscriptType<$Family>,
+            T: $crate::pattern::PatternTranscript,
+            T::State: GroupToUnitDeserialize<G> + CommonGroupToUnit<G>,
+        {
+            fn fill_next_points(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                output: &mut [G],
+            ) -> $crate::ProofResult<()> {
+                let length = $crate::pattern::Length::Fixed(output.len());
+                self.interact::<G, $Family, _, _>($crate::pattern::Kind::Message, label, length, |state| {
+                    GroupToUnitDeserialize::fill_next_points(state, output)
+                })
+            }
+
+            fn public_points(
+                &mut self,
+                label: impl Into<$crate::pattern::Label>,
+                input: &[G],
+            ) -> $crate::ProofResult<()> {
+                let length = $crate::pattern::Length::Fixed(input.len());
+                self.interact::<G, $Family, _, _>($crate::pattern::Kind::Public, label, length, |state| {
+                    CommonGroupToUnit::public_points(state, input).map(|_| ())
+                })
+            }
+        }
     };
 }
 
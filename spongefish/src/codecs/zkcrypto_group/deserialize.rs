@@ -1,19 +1,36 @@
+use alloc::vec::Vec;
+
 use group::ff::PrimeField;
 
 use super::FieldToUnitDeserialize;
-use crate::{BytesToUnitDeserialize, DuplexSpongeInterface, ProofError, VerifierState};
+use crate::{BytesToUnitDeserialize, DuplexSpongeInterface, NargSource, ProofError, VerifierState};
 
-impl<F, H, const N: usize> FieldToUnitDeserialize<F> for VerifierState<'_, H>
+impl<F, H, const N: usize, R> FieldToUnitDeserialize<F> for VerifierState<'_, H, u8, R>
 where
     H: DuplexSpongeInterface,
     F: PrimeField<Repr = [u8; N]>,
+    R: NargSource,
 {
     fn fill_next_scalars(&mut self, output: &mut [F]) -> crate::ProofResult<()> {
         let mut buf = [0u8; N];
         for o in output.iter_mut() {
             self.fill_next_bytes(&mut buf)?;
-            *o = F::from_repr_vartime(buf).ok_or(ProofError::SerializationError)?;
+            *o = F::from_repr_vartime(buf).ok_or(ProofError::SerializationError(None))?;
         }
         Ok(())
     }
+
+    fn next_scalars_vec_bounded(&mut self, max_len: usize) -> crate::ProofResult<Vec<F>> {
+        let len = self.next_units_len_bounded(max_len.saturating_mul(N))?;
+        if len % N != 0 {
+            return Err(ProofError::SerializationError(None));
+        }
+        let mut buf = [0u8; N];
+        let mut output = Vec::new();
+        for _ in 0..len / N {
+            self.fill_next_bytes(&mut buf)?;
+            output.push(F::from_repr_vartime(buf).ok_or(ProofError::SerializationError(None))?);
+        }
+        Ok(output)
+    }
 }
@@ -2,8 +2,8 @@ use group::{ff::PrimeField, Group, GroupEncoding};
 
 use super::{FieldDomainSeparator, GroupDomainSeparator};
 use crate::{
-    codecs::{bytes_modp, bytes_uniform_modp},
-    ByteDomainSeparator, DomainSeparator, DuplexSpongeInterface,
+    codecs::{bytes_modp, bytes_uniform_modp, checked_count},
+    ByteDomainSeparator, DomainSeparator, DomainSeparatorMismatch, DuplexSpongeInterface,
 };
 
 impl<F, H> FieldDomainSeparator<F> for DomainSeparator<H>
@@ -12,11 +12,36 @@ where
     H: DuplexSpongeInterface,
 {
     fn add_scalars(self, count: usize, label: &str) -> Self {
-        self.add_bytes(count * bytes_modp(F::NUM_BITS), label)
+        <Self as FieldDomainSeparator<F>>::try_add_scalars(self, count, label)
+            .unwrap_or_else(|e| panic!("{e}"))
     }
 
     fn challenge_scalars(self, count: usize, label: &str) -> Self {
-        self.challenge_bytes(count * bytes_uniform_modp(F::NUM_BITS), label)
+        <Self as FieldDomainSeparator<F>>::try_challenge_scalars(self, count, label)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn try_add_scalars(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        let n = checked_count(count, bytes_modp(F::NUM_BITS))?;
+        self.try_add_bytes(n, label)
+    }
+
+    fn try_challenge_scalars(
+        self,
+        count: usize,
+        label: &str,
+    ) -> Result<Self, DomainSeparatorMismatch> {
+        let n = checked_count(count, bytes_uniform_modp(F::NUM_BITS))?;
+        self.try_challenge_bytes(n, label)
+    }
+
+    fn try_hint_scalars(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        let n = checked_count(count, F::Repr::default().as_ref().len())?;
+        self.try_hint_bounded(n, label)
+    }
+
+    fn try_add_scalars_dyn(self, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_absorb_dyn(label)
     }
 }
 
@@ -27,7 +52,17 @@ where
     H: DuplexSpongeInterface,
 {
     fn add_points(self, count: usize, label: &str) -> Self {
-        let n = G::Repr::default().as_ref().len();
-        self.add_bytes(count * n, label)
+        <Self as GroupDomainSeparator<G>>::try_add_points(self, count, label)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn try_add_points(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        let n = checked_count(count, G::Repr::default().as_ref().len())?;
+        self.try_add_bytes(n, label)
+    }
+
+    fn try_hint_points(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        let n = checked_count(count, G::Repr::default().as_ref().len())?;
+        self.try_hint_bounded(n, label)
     }
 }
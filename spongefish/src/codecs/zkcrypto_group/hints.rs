@@ -0,0 +1,100 @@
+use alloc::vec::Vec;
+
+use group::{ff::PrimeField, GroupEncoding};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    io, DuplexSpongeInterface, NargSource, ProofError, ProofResult, ProverState, Unit,
+    VerifierState,
+};
+
+/// Send field and group elements as hints.
+///
+/// A hint holds the concatenation of the canonical encodings of its elements,
+/// prefixed by its length as every hint.
+/// Hints are not absorbed in the sponge: the protocol must check them against the transcript.
+pub trait HintSerialize {
+    /// Hint field elements.
+    ///
+    /// This matches the bound set by [`FieldDomainSeparator::hint_scalars`](super::FieldDomainSeparator::hint_scalars).
+    fn hint_scalars<F: PrimeField>(&mut self, hint: &[F]) -> ProofResult<()>;
+
+    /// Hint group elements, or any other value with a fixed-size [`GroupEncoding`].
+    ///
+    /// This matches the bound set by [`GroupDomainSeparator::hint_points`](super::GroupDomainSeparator::hint_points).
+    fn hint_points<G: GroupEncoding>(&mut self, hint: &[G]) -> ProofResult<()>;
+}
+
+/// Receive (and deserialize) hints sent with [`HintSerialize`].
+///
+/// Elements are decoded with [`PrimeField::from_repr`] and [`GroupEncoding::from_bytes`],
+/// which reject non-canonical encodings, and the hint must be consumed entirely.
+pub trait HintDeserialize {
+    /// Deserialize a hint into field elements.
+    fn hint_scalars<F: PrimeField>(&mut self) -> ProofResult<Vec<F>>;
+
+    /// Deserialize a hint into group elements.
+    fn hint_points<G: GroupEncoding>(&mut self) -> ProofResult<Vec<G>>;
+}
+
+impl<H, U, R, P, W> HintSerialize for ProverState<H, U, R, P, W>
+where
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+    R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
+{
+    fn hint_scalars<F: PrimeField>(&mut self, hint: &[F]) -> ProofResult<()> {
+        let buf = hint
+            .iter()
+            .flat_map(|scalar| scalar.to_repr().as_ref().to_vec())
+            .collect::<Vec<u8>>();
+        Ok(self.hint_bytes(&buf)?)
+    }
+
+    fn hint_points<G: GroupEncoding>(&mut self, hint: &[G]) -> ProofResult<()> {
+        let buf = hint
+            .iter()
+            .flat_map(|point| point.to_bytes().as_ref().to_vec())
+            .collect::<Vec<u8>>();
+        Ok(self.hint_bytes(&buf)?)
+    }
+}
+
+impl<H, U, R> HintDeserialize for VerifierState<'_, H, U, R>
+where
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+    R: NargSource,
+    R::Hint: AsRef<[u8]>,
+{
+    fn hint_scalars<F: PrimeField>(&mut self) -> ProofResult<Vec<F>> {
+        let hint = self.hint_bytes()?;
+        decode_elements(hint.as_ref(), |repr: F::Repr| F::from_repr(repr).into())
+    }
+
+    fn hint_points<G: GroupEncoding>(&mut self) -> ProofResult<Vec<G>> {
+        let hint = self.hint_bytes()?;
+        decode_elements(hint.as_ref(), |repr: G::Repr| G::from_bytes(&repr).into())
+    }
+}
+
+/// Decode the concatenation of fixed-size encodings `Repr`.
+fn decode_elements<T, Repr>(bytes: &[u8], decode: impl Fn(Repr) -> Option<T>) -> ProofResult<Vec<T>>
+where
+    Repr: Default + AsRef<[u8]> + AsMut<[u8]>,
+{
+    let size = Repr::default().as_ref().len();
+    if bytes.len() % size != 0 {
+        return Err(ProofError::SerializationError(None));
+    }
+    bytes
+        .chunks_exact(size)
+        .map(|chunk| {
+            let mut repr = Repr::default();
+            repr.as_mut().copy_from_slice(chunk);
+            decode(repr).ok_or(ProofError::SerializationError(None))
+        })
+        .collect()
+}
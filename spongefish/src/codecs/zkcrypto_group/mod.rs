@@ -1,8 +1,13 @@
 //! This adds support also for [curve25519-dalek](https://github.com/dalek-cryptography/curve25519-dalek) with feature flag `group`.
 mod deserialize;
 mod domain_separator;
+mod hints;
 mod prover_messages;
+mod transcript_type;
 mod verifier_messages;
 
-super::traits::field_traits!(group::ff::Field);
-super::traits::group_traits!(group::Group, Scalar: group::ff::Field);
+pub use hints::{HintDeserialize, HintSerialize};
+pub use transcript_type::{FieldFamily, GroupFamily};
+
+super::traits::field_traits!(group::ff::Field, Family: FieldFamily);
+super::traits::group_traits!(group::Group, Scalar: group::ff::Field, Family: GroupFamily);
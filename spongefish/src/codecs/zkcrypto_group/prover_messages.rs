@@ -1,30 +1,64 @@
+use alloc::vec::Vec;
+
 use group::{ff::PrimeField, Group, GroupEncoding};
 use rand::{CryptoRng, RngCore};
 
-use super::{CommonFieldToUnit, CommonGroupToUnit, FieldToUnitSerialize, GroupToUnitSerialize};
+use super::{
+    CommonFieldToUnit, CommonGroupToUnit, FieldToPrivateCoins, FieldToUnitSerialize,
+    GroupToUnitSerialize,
+};
 use crate::{
-    BytesToUnitSerialize, CommonUnitToBytes, DuplexSpongeInterface, ProofResult, ProverState,
+    io, BytesToUnitSerialize, CommonUnitToBytes, DuplexSpongeInterface, ProofResult, ProverState,
+    Unit,
 };
 
-impl<F, H, R> FieldToUnitSerialize<F> for ProverState<H, u8, R>
+impl<F, H, R, P, W> FieldToUnitSerialize<F> for ProverState<H, u8, R, P, W>
 where
     F: PrimeField,
     H: DuplexSpongeInterface,
     R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
 {
     fn add_scalars(&mut self, input: &[F]) -> ProofResult<()> {
         let serialized = self.public_scalars(input);
-        self.narg_string.extend(serialized?);
+        self.write_narg(&serialized?)?;
+        Ok(())
+    }
+
+    fn add_scalars_dyn(&mut self, input: &[F]) -> ProofResult<()> {
+        let mut buf = Vec::new();
+        input.iter().for_each(|i| buf.extend(i.to_repr().as_ref()));
+        self.add_units_dyn(&buf)?;
+        Ok(())
+    }
+}
+
+impl<F, H, U, R, P, W> FieldToPrivateCoins<F> for ProverState<H, U, R, P, W>
+where
+    F: PrimeField,
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+    R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
+{
+    fn absorb_secret_scalars(&mut self, input: &[F]) -> ProofResult<()> {
+        let mut buf = Vec::new();
+        input.iter().for_each(|i| buf.extend(i.to_repr().as_ref()));
+        self.absorb_secret(&buf);
         Ok(())
     }
 }
 
-impl<G, H, R> CommonGroupToUnit<G> for ProverState<H, u8, R>
+impl<G, H, R, P, W> CommonGroupToUnit<G> for ProverState<H, u8, R, P, W>
 where
     G: Group + GroupEncoding,
     G::Repr: AsRef<[u8]>,
     H: DuplexSpongeInterface,
     R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
 {
     type Repr = Vec<u8>;
     fn public_points(&mut self, input: &[G]) -> crate::ProofResult<Self::Repr> {
@@ -37,16 +71,18 @@ where
     }
 }
 
-impl<G, H, R> GroupToUnitSerialize<G> for ProverState<H, u8, R>
+impl<G, H, R, P, W> GroupToUnitSerialize<G> for ProverState<H, u8, R, P, W>
 where
     G: Group + GroupEncoding,
     G::Repr: AsRef<[u8]>,
     H: DuplexSpongeInterface,
     R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
 {
     fn add_points(&mut self, input: &[G]) -> crate::ProofResult<()> {
         let serialized = self.public_points(input);
-        self.narg_string.extend(serialized?);
+        self.write_narg(&serialized?)?;
         Ok(())
     }
 }
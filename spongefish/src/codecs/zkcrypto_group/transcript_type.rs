@@ -0,0 +1,48 @@
+use alloc::{borrow::Cow, format};
+
+use group::{ff::PrimeField, Group, GroupEncoding};
+
+use crate::pattern::TranscriptType;
+
+/// The [`TranscriptType`] family of zkcrypto prime fields.
+///
+/// Fields are identified by their [`PrimeField::MODULUS`].
+pub struct FieldFamily;
+
+/// The [`TranscriptType`] family of zkcrypto groups.
+///
+/// Groups are identified by the encoding of their generator.
+pub struct GroupFamily;
+
+impl<F: PrimeField> TranscriptType<FieldFamily> for F {
+    fn type_id() -> Cow<'static, str> {
+        Cow::Owned(format!("Fp({})", F::MODULUS))
+    }
+}
+
+impl<G: Group + GroupEncoding> TranscriptType<GroupFamily> for G {
+    fn type_id() -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "Group(0x{})",
+            hex::encode(G::generator().to_bytes())
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_ids() {
+        // The modulus is formatted as arkworks does for this field.
+        assert_eq!(
+            <bls12_381::Scalar as TranscriptType<FieldFamily>>::type_id(),
+            <ark_bls12_381::Fr as TranscriptType>::type_id()
+        );
+        assert_ne!(
+            <bls12_381::G1Projective as TranscriptType<GroupFamily>>::type_id(),
+            <bls12_381::G2Projective as TranscriptType<GroupFamily>>::type_id()
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use alloc::vec;
+
 use group::ff::PrimeField;
 
 use super::UnitToField;
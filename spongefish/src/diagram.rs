@@ -0,0 +1,236 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+use crate::pattern::Kind;
+
+/// A step of a protocol, as drawn in a diagram.
+pub enum Step<'a> {
+    /// A public message, a prover message, a hint or a challenge, with its label and size.
+    Interaction {
+        kind: Kind,
+        label: &'a str,
+        size: String,
+    },
+    /// A ratchet of the sponge.
+    Ratchet,
+    /// The beginning of a sub-protocol, with its label.
+    Begin(&'a str),
+    /// The end of the last sub-protocol.
+    End,
+}
+
+/// A protocol rendered as a Graphviz DOT graph or as a Mermaid sequence diagram.
+///
+/// Steps are expected to have balanced [`Step::Begin`] and [`Step::End`].
+pub struct Diagram<'a> {
+    pub title: Option<&'a str>,
+    pub steps: Vec<Step<'a>>,
+}
+
+impl Diagram<'_> {
+    /// A DOT `digraph`: steps are nodes chained in order, sub-protocols are clusters.
+    pub fn to_dot(&self) -> String {
+        alloc::format!("{}", Dot(self))
+    }
+
+    /// A Mermaid `sequenceDiagram` between the prover and the verifier:
+    /// sub-protocols are highlighted boxes.
+    pub fn to_mermaid(&self) -> String {
+        alloc::format!("{}", Mermaid(self))
+    }
+}
+
+/// The text of an interaction, e.g. `commitment (32 units)`.
+fn caption(label: &str, size: &str) -> String {
+    if size.is_empty() {
+        label.into()
+    } else {
+        alloc::format!("{label} ({size})")
+    }
+}
+
+struct Dot<'a>(&'a Diagram<'a>);
+
+impl Dot<'_> {
+    /// Escape a string in a DOT quoted string.
+    fn escape(s: &str) -> String {
+        s.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    }
+}
+
+impl Display for Dot<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "digraph protocol {{")?;
+        if let Some(title) = self.0.title {
+            writeln!(f, "    label=\"{}\";", Self::escape(title))?;
+            writeln!(f, "    labelloc=t;")?;
+        }
+        writeln!(f, "    node [shape=box];")?;
+        let mut depth = 1;
+        let mut clusters = 0;
+        let mut nodes = 0;
+        for step in &self.0.steps {
+            let indent = "    ".repeat(depth);
+            match step {
+                Step::Interaction { kind, label, size } => {
+                    let caption = Self::escape(&caption(label, size));
+                    let (text, style) = match kind {
+                        Kind::Message => (alloc::format!("P → V: {caption}"), ""),
+                        Kind::Hint => (alloc::format!("P → V: hint {caption}"), ", style=dashed"),
+                        Kind::Challenge => (alloc::format!("V → P: {caption}"), ", style=rounded"),
+                        Kind::Public => (alloc::format!("public: {caption}"), ", shape=note"),
+                        Kind::Protocol => (caption, ", shape=note"),
+                    };
+                    writeln!(f, "{indent}step{nodes} [label=\"{text}\"{style}];")?;
+                    nodes += 1;
+                }
+                Step::Ratchet => {
+                    writeln!(f, "{indent}step{nodes} [label=\"ratchet\", shape=diamond];")?;
+                    nodes += 1;
+                }
+                Step::Begin(label) => {
+                    writeln!(f, "{indent}subgraph cluster{clusters} {{")?;
+                    writeln!(f, "{indent}    label=\"{}\";", Self::escape(label))?;
+                    clusters += 1;
+                    depth += 1;
+                }
+                Step::End => {
+                    depth -= 1;
+                    writeln!(f, "{}}}", "    ".repeat(depth))?;
+                }
+            }
+        }
+        for node in 1..nodes {
+            writeln!(f, "    step{} -> step{node};", node - 1)?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+struct Mermaid<'a>(&'a Diagram<'a>);
+
+impl Mermaid<'_> {
+    /// Escape a message text, using Mermaid entity codes for the characters it interprets.
+    fn escape(s: &str) -> String {
+        s.chars().fold(String::new(), |mut escaped, c| {
+            match c {
+                '#' => escaped.push_str("#35;"),
+                ';' => escaped.push_str("#59;"),
+                '<' => escaped.push_str("#lt;"),
+                '>' => escaped.push_str("#gt;"),
+                '\n' => escaped.push(' '),
+                c => escaped.push(c),
+            }
+            escaped
+        })
+    }
+}
+
+impl Display for Mermaid<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "sequenceDiagram")?;
+        writeln!(f, "    participant P as Prover")?;
+        writeln!(f, "    participant V as Verifier")?;
+        if let Some(title) = self.0.title {
+            writeln!(f, "    Note over P,V: {}", Self::escape(title))?;
+        }
+        let mut depth = 1;
+        for step in &self.0.steps {
+            let indent = "    ".repeat(depth);
+            match step {
+                Step::Interaction { kind, label, size } => {
+                    let caption = Self::escape(&caption(label, size));
+                    match kind {
+                        Kind::Message => writeln!(f, "{indent}P->>V: {caption}")?,
+                        Kind::Hint => writeln!(f, "{indent}P-->>V: hint {caption}")?,
+                        Kind::Challenge => writeln!(f, "{indent}V->>P: {caption}")?,
+                        Kind::Public => writeln!(f, "{indent}Note over P,V: public {caption}")?,
+                        Kind::Protocol => writeln!(f, "{indent}Note over P,V: {caption}")?,
+                    }
+                }
+                Step::Ratchet => writeln!(f, "{indent}Note over P,V: ratchet")?,
+                Step::Begin(label) => {
+                    writeln!(f, "{indent}rect rgba(128, 128, 128, 0.1)")?;
+                    writeln!(f, "{indent}Note over P,V: {}", Self::escape(label))?;
+                    depth += 1;
+                }
+                Step::End => {
+                    depth -= 1;
+                    writeln!(f, "{}end", "    ".repeat(depth))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Diagram<'static> {
+        Diagram {
+            title: Some("a \"quoted\"; #title"),
+            steps: vec![
+                Step::Interaction {
+                    kind: Kind::Message,
+                    label: "commitment",
+                    size: "32 units".into(),
+                },
+                Step::Begin("round[0]"),
+                Step::Interaction {
+                    kind: Kind::Hint,
+                    label: "path<\\>",
+                    size: String::new(),
+                },
+                Step::Ratchet,
+                Step::End,
+                Step::Interaction {
+                    kind: Kind::Challenge,
+                    label: "challenge",
+                    size: "16 units".into(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_dot() {
+        let expected = r#"digraph protocol {
+    label="a \"quoted\"; #title";
+    labelloc=t;
+    node [shape=box];
+    step0 [label="P → V: commitment (32 units)"];
+    subgraph cluster0 {
+        label="round[0]";
+        step1 [label="P → V: hint path<\\>", style=dashed];
+        step2 [label="ratchet", shape=diamond];
+    }
+    step3 [label="V → P: challenge (16 units)", style=rounded];
+    step0 -> step1;
+    step1 -> step2;
+    step2 -> step3;
+}
+"#;
+        assert_eq!(example().to_dot(), expected);
+    }
+
+    #[test]
+    fn test_mermaid() {
+        let expected = r##"sequenceDiagram
+    participant P as Prover
+    participant V as Verifier
+    Note over P,V: a "quoted"#59; #35;title
+    P->>V: commitment (32 units)
+    rect rgba(128, 128, 128, 0.1)
+    Note over P,V: round[0]
+        P-->>V: hint path#lt;\#gt;
+        Note over P,V: ratchet
+    end
+    V->>P: challenge (16 units)
+"##;
+        assert_eq!(example().to_mermaid(), expected);
+    }
+}
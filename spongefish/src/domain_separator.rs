@@ -3,13 +3,25 @@
 // which was a pain to use
 // (plain integers don't cast to NonZeroUsize automatically)
 
-use std::{collections::VecDeque, marker::PhantomData};
+use alloc::{
+    collections::VecDeque,
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::{fmt, marker::PhantomData, str::FromStr};
 
 use super::{
-    duplex_sponge::{DuplexSpongeInterface, Unit},
-    errors::DomainSeparatorMismatch,
+    duplex_sponge::{DuplexSponge, DuplexSpongeInterface, Permutation, Unit},
+    errors::{DomainSeparatorMismatch, ParseDomainSeparatorError},
+};
+use crate::{
+    diagram::{Diagram, Step},
+    pattern::Kind,
+    sho::CompiledDomainSeparator,
+    ByteDomainSeparator,
 };
-use crate::ByteDomainSeparator;
 
 /// This is the separator between operations in the domain separator
 /// and as such is the only forbidden character in labels.
@@ -27,6 +39,7 @@ const SEP_BYTE: &str = "\0";
 /// The letter `A` indicates the absorption of a public input (an `ABSORB`), while the letter `S` indicates the squeezing (a `SQUEEZE`) of a challenge.
 /// The letter `R` indicates a ratcheting operation: ratcheting means invoking the hash function even on an incomplete block.
 /// It provides forward secrecy and allows it to start from a clean rate.
+/// Sub-protocols are enclosed between `(label` and `)` (see [`DomainSeparator::nest`]).
 /// After the operation type, is the number of elements in base 10 that are being absorbed/squeezed.
 /// Then, follows the label associated with the element being absorbed/squeezed. This often comes from the underlying description of the protocol. The label cannot start with a digit or contain the NULL byte.
 ///
@@ -53,11 +66,18 @@ pub enum Op {
     ///
     /// In a tag, absorb is indicated with 'A'.
     Absorb(usize),
+    /// Indicates absorption of a variable number of lanes,
+    /// preceded by the absorption of their number.
+    ///
+    /// In a tag, dynamic absorb is indicated with 'D'.
+    AbsorbDyn,
     /// Indicates processing of out-of-band message
-    /// from prover to verifier.
+    /// from prover to verifier, optionally bounded to `usize` bytes.
     ///
     /// This is useful for e.g. adding merkle proofs to the proof.
-    Hint,
+    ///
+    /// In a tag, hint is indicated with 'H', followed by the bound if any.
+    Hint(Option<usize>),
     /// Indicates squeezing of `usize` lanes.
     ///
     /// In a tag, squeeze is indicated with 'S'.
@@ -69,6 +89,71 @@ pub enum Op {
     /// This allows for a more efficient preprocessing, and for removal of
     /// private information stored in the rate.
     Ratchet,
+    /// Indicates the beginning of a nested sub-protocol,
+    /// whose label prefixes the labels of its operations.
+    ///
+    /// In a tag, it is indicated with '(', followed by the label of the sub-protocol.
+    Begin,
+    /// Indicates the end of a nested sub-protocol.
+    ///
+    /// In a tag, it is indicated with ')'.
+    End,
+}
+
+/// An [`Op`] together with its position and label in the domain separator.
+///
+/// The label is the path of the operation, e.g. `outer/round[3]/challenge`
+/// for an operation labeled `challenge` in nested sub-protocols.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LabeledOp {
+    pub op: Op,
+    pub index: usize,
+    pub label: Arc<str>,
+}
+
+/// The size of the NARG string described by a domain separator, as returned by [`DomainSeparator::narg_size`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct NargSize {
+    /// The number of absorbed units.
+    pub units: usize,
+    /// The number of bytes taken by the absorbed units in the NARG string.
+    pub bytes: usize,
+    /// The number of hints.
+    ///
+    /// Hints have variable length: each one takes 4 bytes for its length, plus its content.
+    pub hints: usize,
+    /// The bound on the total length of the hints' content,
+    /// if all hints are bounded (see [`DomainSeparator::hint_bounded`]).
+    pub max_hint_bytes: Option<usize>,
+    /// The number of variable-length prover messages (see [`DomainSeparator::absorb_dyn`]).
+    ///
+    /// Each one takes 4 bytes for its length, plus its content.
+    pub dynamic: usize,
+}
+
+impl NargSize {
+    /// Return the length of the NARG string,
+    /// given the total length of the content of hints and variable-length prover messages.
+    #[must_use]
+    pub const fn with_variable(&self, variable_bytes: usize) -> usize {
+        self.bytes + 4 * (self.hints + self.dynamic) + variable_bytes
+    }
+}
+
+impl fmt::Display for Op {
+    /// Format the operation as in the domain separator string, e.g. `A32` or `R`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Absorb(count) => write!(f, "A{count}"),
+            Self::AbsorbDyn => write!(f, "D"),
+            Self::Hint(None) => write!(f, "H"),
+            Self::Hint(Some(max_len)) => write!(f, "H{max_len}"),
+            Self::Squeeze(count) => write!(f, "S{count}"),
+            Self::Ratchet => write!(f, "R"),
+            Self::Begin => write!(f, "("),
+            Self::End => write!(f, ")"),
+        }
+    }
 }
 
 impl Op {
@@ -76,15 +161,58 @@ impl Op {
     fn new(id: char, count: Option<usize>) -> Result<Self, DomainSeparatorMismatch> {
         match (id, count) {
             ('A', Some(c)) if c > 0 => Ok(Self::Absorb(c)),
-            ('H', None | Some(0)) => Ok(Self::Hint),
+            ('D', None) => Ok(Self::AbsorbDyn),
+            ('H', None) => Ok(Self::Hint(None)),
+            ('H', Some(c)) if c > 0 => Ok(Self::Hint(Some(c))),
             ('R', None | Some(0)) => Ok(Self::Ratchet),
             ('S', Some(c)) if c > 0 => Ok(Self::Squeeze(c)),
+            ('(', None) => Ok(Self::Begin),
+            (')', None) => Ok(Self::End),
             _ => Err("Invalid tag".into()),
         }
     }
+
+    /// Parse the `index`-th operation of a domain separator string, returning it together with its label.
+    fn parse(index: usize, part: &str) -> Result<(Self, &str), ParseDomainSeparatorError> {
+        let id = part
+            .chars()
+            .next()
+            .ok_or(ParseDomainSeparatorError::EmptyOp { index })?;
+        let rest = &part[id.len_utf8()..];
+        let (digits, label) = rest.split_at(
+            rest.find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len()),
+        );
+
+        // counts are written in their canonical form, without leading zeros
+        let count = match digits.as_bytes() {
+            [] => None,
+            [b'0', ..] => return Err(ParseDomainSeparatorError::InvalidCount { index, id }),
+            _ => Some(
+                digits
+                    .parse()
+                    .map_err(|_| ParseDomainSeparatorError::InvalidCount { index, id })?,
+            ),
+        };
+
+        let op = Self::new(id, count).map_err(|_| match id {
+            'A' | 'H' | 'S' => ParseDomainSeparatorError::InvalidCount { index, id },
+            'D' | 'R' | '(' | ')' => ParseDomainSeparatorError::UnexpectedCount { index, id },
+            _ => ParseDomainSeparatorError::UnknownOp { index, id },
+        })?;
+        if matches!(op, Self::Ratchet | Self::End) && !label.is_empty() {
+            return Err(ParseDomainSeparatorError::UnexpectedLabel { index, id });
+        }
+        Ok((op, label))
+    }
 }
 
 impl<H: DuplexSpongeInterface<U>, U: Unit> DomainSeparator<H, U> {
+    /// Create a domain separator from its string encoding, without validating it.
+    ///
+    /// Malformed domain separators will cause a panic when used.
+    /// To load domain separators from untrusted sources, use [`DomainSeparator::try_from_bytes`]
+    /// or [`str::parse`] instead.
     #[must_use]
     pub const fn from_string(io: String) -> Self {
         Self {
@@ -94,62 +222,260 @@ impl<H: DuplexSpongeInterface<U>, U: Unit> DomainSeparator<H, U> {
     }
 
     /// Create a new DomainSeparator with the domain separator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the session identifier contains the NUL byte.
+    /// See [`DomainSeparator::try_new`] for a non-panicking version.
     #[must_use]
     pub fn new(session_identifier: &str) -> Self {
-        assert!(
-            !session_identifier.contains(SEP_BYTE),
-            "Domain separator cannot contain the separator BYTE."
-        );
-        Self::from_string(session_identifier.to_string())
+        Self::try_new(session_identifier).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Create a new DomainSeparator with the domain separator,
+    /// returning an error if the session identifier contains the NUL byte.
+    pub fn try_new(session_identifier: &str) -> Result<Self, DomainSeparatorMismatch> {
+        if session_identifier.contains(SEP_BYTE) {
+            return Err("Domain separator cannot contain the separator BYTE.".into());
+        }
+        Ok(Self::from_string(session_identifier.to_string()))
     }
 
     /// Absorb `count` native elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is zero, or if the label is invalid.
+    /// See [`DomainSeparator::try_absorb`] for a non-panicking version.
     #[must_use]
     pub fn absorb(self, count: usize, label: &str) -> Self {
-        assert!(count > 0, "Count must be positive.");
-        assert!(
-            !label.contains(SEP_BYTE),
-            "Label cannot contain the separator BYTE."
-        );
-        assert!(
-            label
-                .chars()
-                .next()
-                .is_none_or(|char| !char.is_ascii_digit()),
-            "Label cannot start with a digit."
-        );
+        self.try_absorb(count, label)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Absorb `count` native elements,
+    /// returning an error if `count` is zero or if the label is invalid.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝");
+    /// assert!(domsep.clone().try_absorb(0, "empty").is_err());
+    /// assert!(domsep.clone().try_absorb(1, "1st").is_err());
+    /// assert!(domsep.try_absorb(1, "first").is_ok());
+    /// ```
+    pub fn try_absorb(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        if count == 0 {
+            return Err("Count must be positive.".into());
+        }
+        Self::check_label(label)?;
 
-        Self::from_string(self.io + SEP_BYTE + &format!("A{count}") + label)
+        Ok(Self::from_string(
+            self.io + SEP_BYTE + &format!("A{count}") + label,
+        ))
+    }
+
+    /// Absorb a variable number of native elements, only known when running the protocol.
+    ///
+    /// Their number is absorbed first, encoded over 4 units,
+    /// so that messages of different lengths cannot be confused.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the label is invalid.
+    /// See [`DomainSeparator::try_absorb_dyn`] for a non-panicking version.
+    #[must_use]
+    pub fn absorb_dyn(self, label: &str) -> Self {
+        self.try_absorb_dyn(label).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Absorb a variable number of native elements, returning an error if the label is invalid.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝").absorb_dyn("📜").squeeze(16, "🎲");
+    /// assert_eq!(domsep.as_bytes(), "📝\0D📜\0S16🎲".as_bytes());
+    ///
+    /// let mut prover_state = domsep.to_prover_state();
+    /// prover_state.add_units_dyn(b"any length").unwrap();
+    /// assert_eq!(prover_state.narg_string(), b"\x0a\0\0\0any length");
+    /// ```
+    pub fn try_absorb_dyn(self, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        Self::check_label(label)?;
+
+        Ok(Self::from_string(self.io + SEP_BYTE + "D" + label))
     }
 
     /// Hint `count` native elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the label is invalid.
+    /// See [`DomainSeparator::try_hint`] for a non-panicking version.
     #[must_use]
     pub fn hint(self, label: &str) -> Self {
-        assert!(
-            !label.contains(SEP_BYTE),
-            "Label cannot contain the separator BYTE."
-        );
+        self.try_hint(label).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Hint `count` native elements, returning an error if the label is invalid.
+    pub fn try_hint(self, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        Self::check_label(label)?;
+
+        Ok(Self::from_string(self.io + SEP_BYTE + "H" + label))
+    }
+
+    /// Hint at most `max_len` bytes.
+    ///
+    /// The prover fails to send, and the verifier refuses to read, longer hints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_len` is zero, or if the label is invalid.
+    /// See [`DomainSeparator::try_hint_bounded`] for a non-panicking version.
+    #[must_use]
+    pub fn hint_bounded(self, max_len: usize, label: &str) -> Self {
+        self.try_hint_bounded(max_len, label)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
 
-        Self::from_string(self.io + SEP_BYTE + "H" + label)
+    /// Hint at most `max_len` bytes,
+    /// returning an error if `max_len` is zero or if the label is invalid.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝").hint_bounded(4, "🌳");
+    /// assert_eq!(domsep.as_bytes(), "📝\0H4🌳".as_bytes());
+    ///
+    /// let mut prover_state = domsep.to_prover_state();
+    /// assert!(prover_state.hint_bytes(b"too long").is_err());
+    /// ```
+    pub fn try_hint_bounded(
+        self,
+        max_len: usize,
+        label: &str,
+    ) -> Result<Self, DomainSeparatorMismatch> {
+        if max_len == 0 {
+            return Err("Count must be positive.".into());
+        }
+        Self::check_label(label)?;
+
+        Ok(Self::from_string(
+            self.io + SEP_BYTE + &format!("H{max_len}") + label,
+        ))
     }
 
     /// Squeeze `count` native elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is zero, or if the label is invalid.
+    /// See [`DomainSeparator::try_squeeze`] for a non-panicking version.
     #[must_use]
     pub fn squeeze(self, count: usize, label: &str) -> Self {
-        assert!(count > 0, "Count must be positive.");
-        assert!(
-            !label.contains(SEP_BYTE),
-            "Label cannot contain the separator BYTE."
-        );
-        assert!(
-            label
-                .chars()
-                .next()
-                .is_none_or(|char| !char.is_ascii_digit()),
-            "Label cannot start with a digit."
-        );
+        self.try_squeeze(count, label)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Squeeze `count` native elements,
+    /// returning an error if `count` is zero or if the label is invalid.
+    pub fn try_squeeze(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        if count == 0 {
+            return Err("Count must be positive.".into());
+        }
+        Self::check_label(label)?;
+
+        Ok(Self::from_string(
+            self.io + SEP_BYTE + &format!("S{count}") + label,
+        ))
+    }
 
-        Self::from_string(self.io + SEP_BYTE + &format!("S{count}") + label)
+    /// Embed the sub-protocol `sub`, whose labels are prefixed by `label`.
+    ///
+    /// The operations of `sub` are enclosed between `(label` and `)`,
+    /// so that the hierarchy is unambiguous whatever the labels.
+    /// The session identifier of `sub` is dropped: `label` identifies the sub-protocol.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the label is invalid.
+    /// See [`DomainSeparator::try_nest`] for a non-panicking version.
+    #[must_use]
+    pub fn nest(self, label: &str, sub: Self) -> Self {
+        self.try_nest(label, sub).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Embed the sub-protocol `sub`, returning an error if the label is invalid.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let schnorr = DomainSeparator::<DefaultHash>::new("schnorr").absorb(32, "commitment").squeeze(32, "challenge");
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝").try_nest("proof", schnorr).unwrap();
+    /// assert_eq!(domsep.as_bytes(), "📝\0(proof\0A32commitment\0S32challenge\0)".as_bytes());
+    ///
+    /// let mut prover_state = domsep.to_prover_state();
+    /// let err = prover_state.challenge_bytes::<32>().unwrap_err();
+    /// assert_eq!(err.to_string(), "Expected A32 'proof/commitment' at op #1, got S32");
+    /// ```
+    pub fn try_nest(self, label: &str, sub: Self) -> Result<Self, DomainSeparatorMismatch> {
+        Self::check_label(label)?;
+
+        let sub_io = sub.io;
+        let sub_ops = sub_io.find(SEP_BYTE).map_or("", |start| &sub_io[start..]);
+        Ok(Self::from_string(
+            self.io + SEP_BYTE + "(" + label + sub_ops + SEP_BYTE + ")",
+        ))
+    }
+
+    /// Repeat a sub-protocol `count` times, nesting the `i`-th iteration under `label[i]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the label is invalid.
+    /// See [`DomainSeparator::try_repeat`] for a non-panicking version.
+    #[must_use]
+    pub fn repeat(self, count: usize, label: &str, mut f: impl FnMut(Self, usize) -> Self) -> Self {
+        self.try_repeat(count, label, |domsep, i| Ok(f(domsep, i)))
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Repeat a sub-protocol `count` times, nesting the `i`-th iteration under `label[i]`,
+    /// returning an error if the label is invalid or if `f` fails.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝")
+    ///     .repeat(2, "round", |domsep, _| domsep.absorb(1, "message").squeeze(1, "challenge"));
+    /// let paths = domsep.paths().map(|(_, path)| path).collect::<Vec<_>>();
+    /// assert_eq!(paths, ["round[0]/message", "round[0]/challenge", "round[1]/message", "round[1]/challenge"]);
+    /// ```
+    pub fn try_repeat(
+        self,
+        count: usize,
+        label: &str,
+        mut f: impl FnMut(Self, usize) -> Result<Self, DomainSeparatorMismatch>,
+    ) -> Result<Self, DomainSeparatorMismatch> {
+        Self::check_label(label)?;
+
+        (0..count).try_fold(self, |domsep, i| {
+            let domsep = Self::from_string(domsep.io + SEP_BYTE + &format!("({label}[{i}]"));
+            let domsep = f(domsep, i)?;
+            Ok(Self::from_string(domsep.io + SEP_BYTE + ")"))
+        })
+    }
+
+    /// Labels cannot contain the separator byte, nor start with an ASCII digit.
+    fn check_label(label: &str) -> Result<(), DomainSeparatorMismatch> {
+        if label.contains(SEP_BYTE) {
+            return Err("Label cannot contain the separator BYTE.".into());
+        }
+        if label.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err("Label cannot start with a digit.".into());
+        }
+        Ok(())
     }
 
     /// Ratchet the state.
@@ -158,61 +484,291 @@ impl<H: DuplexSpongeInterface<U>, U: Unit> DomainSeparator<H, U> {
         Self::from_string(self.io + SEP_BYTE + "R")
     }
 
+    /// Parse and validate a domain separator from its byte encoding.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domsep = DomainSeparator::<DefaultHash>::try_from_bytes(b"proto\0A32commitment\0S16challenge").unwrap();
+    /// assert_eq!(domsep.as_bytes(), DomainSeparator::<DefaultHash>::new("proto").absorb(32, "commitment").squeeze(16, "challenge").as_bytes());
+    /// assert!(DomainSeparator::<DefaultHash>::try_from_bytes(b"proto\0A0commitment").is_err());
+    /// ```
+    pub fn try_from_bytes(domain_separator: &[u8]) -> Result<Self, ParseDomainSeparatorError> {
+        core::str::from_utf8(domain_separator)
+            .map_err(|_| ParseDomainSeparatorError::InvalidUtf8)?
+            .parse()
+    }
+
     /// Return the domain separator as bytes.
     #[must_use]
     pub fn as_bytes(&self) -> &[u8] {
         self.io.as_bytes()
     }
 
+    /// Iterate over the operations of the domain separator, together with their labels.
+    ///
+    /// Unlike the sponge operations performed by the prover and the verifier,
+    /// consecutive absorb (resp. squeeze) operations are *not* merged.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domsep = DomainSeparator::<DefaultHash>::new("proto").absorb(32, "commitment").ratchet();
+    /// let ops = domsep.ops().collect::<Vec<_>>();
+    /// assert_eq!(ops, [(Op::Absorb(32), "commitment"), (Op::Ratchet, "")]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the domain separator was built with [`DomainSeparator::from_string`] from a malformed string.
+    pub fn ops(&self) -> impl Iterator<Item = (Op, &str)> {
+        Self::parse_ops(&self.io).map(|op| op.expect("Malformed domain separator"))
+    }
+
+    /// Iterate over the sponge operations of the domain separator, together with their path.
+    ///
+    /// The path of an operation is its label, prefixed by the labels of the sub-protocols
+    /// it is nested in (see [`DomainSeparator::nest`]) and separated by `/`.
+    /// As with [`DomainSeparator::ops`], operations are not merged.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let sub = DomainSeparator::<DefaultHash>::new("sub").squeeze(16, "challenge");
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝").absorb(1, "statement").nest("outer", sub);
+    /// let paths = domsep.paths().collect::<Vec<_>>();
+    /// assert_eq!(paths, [(Op::Absorb(1), "statement".into()), (Op::Squeeze(16), "outer/challenge".into())]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the domain separator was built with [`DomainSeparator::from_string`] from a malformed string.
+    pub fn paths(&self) -> impl Iterator<Item = (Op, String)> {
+        Self::parse_paths(&self.io)
+            .expect("Malformed domain separator")
+            .into_iter()
+            .map(|op| (op.op, op.label.to_string()))
+    }
+
+    /// Return the size of the NARG string of the proofs following this domain separator.
+    ///
+    /// Every absorbed unit is counted as a prover message.
+    /// Public messages (e.g. absorbed with [`crate::CommonUnitToBytes::public_bytes`])
+    /// are not written in the NARG string, and should be subtracted.
+    /// Variable-length prover messages are only counted in [`NargSize::dynamic`].
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝").absorb(32, "🔒").squeeze(16, "🎲").hint_bounded(100, "🌳");
+    /// let size = domsep.narg_size();
+    /// assert_eq!(size, NargSize { units: 32, bytes: 32, hints: 1, max_hint_bytes: Some(100), dynamic: 0 });
+    /// assert_eq!(size.with_variable(100), 32 + 4 + 100);
+    ///
+    /// let size = domsep.hint("🍃").narg_size();
+    /// assert_eq!(size.max_hint_bytes, None);
+    /// ```
+    #[must_use]
+    pub fn narg_size(&self) -> NargSize
+    where
+        U: Default,
+    {
+        let mut size = NargSize {
+            max_hint_bytes: Some(0),
+            ..NargSize::default()
+        };
+        for (op, _) in self.ops() {
+            match op {
+                Op::Absorb(count) => size.units += count,
+                Op::AbsorbDyn => size.dynamic += 1,
+                Op::Hint(max_len) => {
+                    size.hints += 1;
+                    size.max_hint_bytes = size.max_hint_bytes.zip(max_len).map(|(a, b)| a + b);
+                }
+                Op::Squeeze(_) | Op::Ratchet | Op::Begin | Op::End => (),
+            }
+        }
+        size.bytes = size.units * U::encoded_size();
+        size
+    }
+
+    /// Render the protocol as a Graphviz DOT graph, titled with the session identifier.
+    ///
+    /// Absorptions are drawn as prover messages and squeezes as challenges, with their number of units,
+    /// while sub-protocols (see [`DomainSeparator::nest`]) are drawn as clusters.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let round = DomainSeparator::<DefaultHash>::new("round").absorb(32, "commitment").squeeze(16, "challenge");
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝").nest("round[0]", round).ratchet();
+    /// let dot = domsep.to_dot();
+    /// assert!(dot.starts_with("digraph protocol {\n    label=\"📝\";"));
+    /// assert!(dot.contains("subgraph cluster0 {\n        label=\"round[0]\";"));
+    /// assert!(dot.contains("step0 [label=\"P → V: commitment (32 units)\"];"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the domain separator was built with [`DomainSeparator::from_string`] from a malformed string.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        self.diagram().to_dot()
+    }
+
+    /// Render the protocol as a Mermaid sequence diagram between the prover and the verifier.
+    ///
+    /// See [`DomainSeparator::to_dot`].
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝").absorb(32, "commitment").squeeze(16, "challenge");
+    /// let mermaid = domsep.to_mermaid();
+    /// assert!(mermaid.ends_with("P->>V: commitment (32 units)\n    V->>P: challenge (16 units)\n"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the domain separator was built with [`DomainSeparator::from_string`] from a malformed string.
+    #[must_use]
+    pub fn to_mermaid(&self) -> String {
+        self.diagram().to_mermaid()
+    }
+
+    fn diagram(&self) -> Diagram<'_> {
+        let steps = self
+            .ops()
+            .map(|(op, label)| {
+                let (kind, size) = match op {
+                    Op::Absorb(count) => (Kind::Message, format!("{count} units")),
+                    Op::AbsorbDyn => (Kind::Message, "variable".to_string()),
+                    Op::Hint(None) => (Kind::Hint, "variable".to_string()),
+                    Op::Hint(Some(max_len)) => (Kind::Hint, format!("≤ {max_len} bytes")),
+                    Op::Squeeze(count) => (Kind::Challenge, format!("{count} units")),
+                    Op::Ratchet => return Step::Ratchet,
+                    Op::Begin => return Step::Begin(label),
+                    Op::End => return Step::End,
+                };
+                Step::Interaction { kind, label, size }
+            })
+            .collect();
+        Diagram {
+            title: self.io.split(SEP_BYTE).next(),
+            steps,
+        }
+    }
+
     /// Parse the givern domain separator into a sequence of [`Op`]'s.
+    #[cfg(test)]
     pub(crate) fn finalize(&self) -> VecDeque<Op> {
+        self.labeled_ops().into_iter().map(|op| op.op).collect()
+    }
+
+    /// Parse the domain separator into operations, keeping track of their position and label.
+    pub(crate) fn labeled_ops(&self) -> VecDeque<LabeledOp> {
         // Guaranteed to succeed as instances are all valid domain_separators
         Self::parse_domsep(self.io.as_bytes())
             .expect("Internal error. Please submit issue to m@orru.net")
     }
 
-    fn parse_domsep(domain_separator: &[u8]) -> Result<VecDeque<Op>, DomainSeparatorMismatch> {
-        let mut stack = VecDeque::new();
-
+    fn parse_ops(
+        domain_separator: &str,
+    ) -> impl Iterator<Item = Result<(Op, &str), ParseDomainSeparatorError>> {
         // skip the domain separator
-        for part in domain_separator
-            .split(|&b| b == SEP_BYTE.as_bytes()[0])
+        domain_separator
+            .split(SEP_BYTE)
             .skip(1)
-        {
-            let next_id = part[0] as char;
-            let next_length = part[1..]
-                .iter()
-                .take_while(|x| x.is_ascii_digit())
-                .fold(0, |acc, x| acc * 10 + (x - b'0') as usize);
-
-            // check that next_length != 0 is performed internally on Op::new
-            let next_op = Op::new(next_id, Some(next_length))?;
-            stack.push_back(next_op);
-        }
+            .enumerate()
+            .map(|(index, part)| Op::parse(index, part))
+    }
+
+    fn parse_domsep(
+        domain_separator: &[u8],
+    ) -> Result<VecDeque<LabeledOp>, ParseDomainSeparatorError> {
+        let domain_separator = core::str::from_utf8(domain_separator)
+            .map_err(|_| ParseDomainSeparatorError::InvalidUtf8)?;
+        let stack = Self::parse_paths(domain_separator)?;
 
         // consecutive calls are merged into one
-        match stack.pop_front() {
-            None => Ok(stack),
-            Some(x) => Ok(Self::simplify_stack([x].into(), stack)),
+        Ok(Self::simplify_stack(stack))
+    }
+
+    /// Parse the domain separator into sponge operations labeled with their path,
+    /// checking that nested sub-protocols are balanced.
+    fn parse_paths(
+        domain_separator: &str,
+    ) -> Result<VecDeque<LabeledOp>, ParseDomainSeparatorError> {
+        let mut stack = VecDeque::new();
+        // the position and label of the open sub-protocols
+        let mut nesting = Vec::<(usize, &str)>::new();
+        for (index, op) in Self::parse_ops(domain_separator).enumerate() {
+            let (op, label) = op?;
+            match op {
+                Op::Begin => nesting.push((index, label)),
+                Op::End => {
+                    nesting
+                        .pop()
+                        .ok_or(ParseDomainSeparatorError::UnmatchedEnd { index })?;
+                }
+                _ => stack.push_back(LabeledOp {
+                    op,
+                    index,
+                    label: nesting
+                        .iter()
+                        .map(|&(_, prefix)| prefix)
+                        .chain([label])
+                        .collect::<Vec<_>>()
+                        .join("/")
+                        .into(),
+                }),
+            }
         }
+        if let Some(&(index, _)) = nesting.first() {
+            return Err(ParseDomainSeparatorError::UnclosedNest { index });
+        }
+        Ok(stack)
     }
 
-    fn simplify_stack(mut dst: VecDeque<Op>, mut stack: VecDeque<Op>) -> VecDeque<Op> {
-        while let Some(next) = stack.pop_front() {
-            match (dst.pop_back(), next) {
-                (Some(Op::Squeeze(a)), Op::Squeeze(b)) => dst.push_back(Op::Squeeze(a + b)),
-                (Some(Op::Absorb(a)), Op::Absorb(b)) => dst.push_back(Op::Absorb(a + b)),
-                (Some(prev), next) => {
-                    dst.push_back(prev);
-                    dst.push_back(next);
+    /// Merge consecutive absorb (resp. squeeze) operations,
+    /// keeping the position and label of the first one.
+    fn simplify_stack(stack: VecDeque<LabeledOp>) -> VecDeque<LabeledOp> {
+        let mut dst = VecDeque::<LabeledOp>::with_capacity(stack.len());
+        for next in stack {
+            match (dst.back_mut().map(|prev| &mut prev.op), next.op) {
+                (Some(Op::Squeeze(a)), Op::Squeeze(b)) | (Some(Op::Absorb(a)), Op::Absorb(b)) => {
+                    *a += b;
                 }
-                (None, next) => dst.push_back(next),
+                _ => dst.push_back(next),
             }
         }
         dst
     }
 
+    /// Parse and hash the domain separator once, for creating many prover or verifier states.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domain_separator = DomainSeparator::<DefaultHash>::new("📝").absorb(1, "🥚").squeeze(16, "🐣");
+    /// let compiled = domain_separator.compile();
+    ///
+    /// let mut prover_state = compiled.to_prover_state();
+    /// prover_state.add_bytes(b"!").unwrap();
+    /// let challenge = prover_state.challenge_bytes::<16>().unwrap();
+    /// let narg_string = prover_state.narg_string();
+    ///
+    /// let mut verifier_state = domain_separator.to_verifier_state(narg_string);
+    /// assert_eq!(verifier_state.next_bytes().unwrap(), *b"!");
+    /// assert_eq!(verifier_state.challenge_bytes::<16>().unwrap(), challenge);
+    /// ```
+    #[must_use]
+    pub fn compile(&self) -> CompiledDomainSeparator<H, U> {
+        CompiledDomainSeparator::new(self)
+    }
+
     /// Create an [`crate::ProverState`] instance from the domain separator.
+    #[cfg(feature = "std")]
     #[must_use]
     pub fn to_prover_state(&self) -> crate::ProverState<H, U, crate::DefaultRng> {
         self.into()
@@ -225,6 +781,89 @@ impl<H: DuplexSpongeInterface<U>, U: Unit> DomainSeparator<H, U> {
     }
 }
 
+impl<C: Permutation> DomainSeparator<DuplexSponge<C>, C::U> {
+    /// Return the number of permutation calls performed on the public sponge
+    /// by the prover (or the verifier) following this domain separator.
+    ///
+    /// This counts neither the hashing of the domain separator into the initial state of the sponge,
+    /// which only depends on the protocol (see [`DomainSeparator::compile`]),
+    /// nor the calls made by the prover's private sponge.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// // The Keccak sponge has a rate of 136 bytes.
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝").absorb(200, "🔒").squeeze(32, "🎲").ratchet();
+    /// assert_eq!(domsep.permutation_calls(), 3);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the domain separator has variable-length absorptions:
+    /// use [`Self::permutation_calls_with`] instead.
+    #[must_use]
+    pub fn permutation_calls(&self) -> usize {
+        self.permutation_calls_with(&[])
+    }
+
+    /// Return the number of permutation calls performed on the public sponge,
+    /// given the lengths of the variable-length absorptions (see [`DomainSeparator::absorb_dyn`]), in order.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝").absorb_dyn("📜").squeeze(32, "🎲");
+    /// assert_eq!(domsep.permutation_calls_with(&[100]), 1);
+    /// assert_eq!(domsep.permutation_calls_with(&[200]), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer lengths than variable-length absorptions are given.
+    #[must_use]
+    pub fn permutation_calls_with(&self, dynamic_lengths: &[usize]) -> usize {
+        // The sponge permutes lazily, when a full rate has to be overwritten or read again.
+        // Starting from `pos` units in the current block, processing `count` more units
+        // leaves the sponge at `(pos + count - 1) % R + 1`.
+        let blocks = |pos: &mut usize, count: usize| {
+            let end = *pos + count - 1;
+            *pos = end % C::R + 1;
+            end / C::R
+        };
+
+        let mut dynamic_lengths = dynamic_lengths.iter();
+        let (mut absorb_pos, mut squeeze_pos) = (0, C::R);
+        let mut calls = 0;
+        for (op, _) in self.ops() {
+            match op {
+                Op::Absorb(count) => {
+                    squeeze_pos = C::R;
+                    calls += blocks(&mut absorb_pos, count);
+                }
+                Op::AbsorbDyn => {
+                    let count = dynamic_lengths
+                        .next()
+                        .expect("Missing length of a variable-length absorption");
+                    squeeze_pos = C::R;
+                    // the length prefix is absorbed together with the content
+                    calls += blocks(&mut absorb_pos, 4 + count);
+                }
+                Op::Squeeze(count) => {
+                    absorb_pos = 0;
+                    calls += blocks(&mut squeeze_pos, count);
+                }
+                Op::Ratchet => {
+                    // absorption resumes at the same position after a ratchet
+                    squeeze_pos = C::R;
+                    calls += 1;
+                }
+                Op::Hint(_) | Op::Begin | Op::End => (),
+            }
+        }
+        calls
+    }
+}
+
 impl<U: Unit, H: DuplexSpongeInterface<U>> core::fmt::Debug for DomainSeparator<H, U> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         // Ensure that the state isn't accidentally logged
@@ -232,6 +871,15 @@ impl<U: Unit, H: DuplexSpongeInterface<U>> core::fmt::Debug for DomainSeparator<
     }
 }
 
+impl<U: Unit, H: DuplexSpongeInterface<U>> FromStr for DomainSeparator<H, U> {
+    type Err = ParseDomainSeparatorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_paths(s)?;
+        Ok(Self::from_string(s.to_string()))
+    }
+}
+
 impl<H: DuplexSpongeInterface> ByteDomainSeparator for DomainSeparator<H> {
     #[inline]
     fn add_bytes(self, count: usize, label: &str) -> Self {
@@ -246,6 +894,32 @@ impl<H: DuplexSpongeInterface> ByteDomainSeparator for DomainSeparator<H> {
     fn challenge_bytes(self, count: usize, label: &str) -> Self {
         self.squeeze(count, label)
     }
+
+    #[inline]
+    fn try_add_bytes(self, count: usize, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_absorb(count, label)
+    }
+
+    fn try_hint(self, label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_hint(label)
+    }
+
+    fn try_hint_bounded(
+        self,
+        max_len: usize,
+        label: &str,
+    ) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_hint_bounded(max_len, label)
+    }
+
+    #[inline]
+    fn try_challenge_bytes(
+        self,
+        count: usize,
+        label: &str,
+    ) -> Result<Self, DomainSeparatorMismatch> {
+        self.try_squeeze(count, label)
+    }
 }
 
 #[cfg(test)]
@@ -258,7 +932,7 @@ mod tests {
     #[test]
     fn test_op_new_invalid_cases() {
         assert!(Op::new('A', Some(0)).is_err()); // absorb with zero
-        assert!(Op::new('H', Some(1)).is_err()); // hint with size
+        assert!(Op::new('H', Some(0)).is_err()); // hint bounded by zero
         assert!(Op::new('S', Some(0)).is_err()); // squeeze with zero
         assert!(Op::new('X', Some(1)).is_err()); // invalid op char
         assert!(Op::new('R', Some(5)).is_err()); // R doesn't support > 0
@@ -393,6 +1067,82 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn test_parse_round_trip() {
+        let ds = DomainSeparator::<H>::new("proto 🦀")
+            .absorb(2, "a")
+            .hint("h")
+            .ratchet()
+            .squeeze(16, "c");
+        let parsed: DomainSeparator<H> =
+            std::str::from_utf8(ds.as_bytes()).unwrap().parse().unwrap();
+        assert_eq!(parsed.as_bytes(), ds.as_bytes());
+        let parsed = DomainSeparator::<H>::try_from_bytes(ds.as_bytes()).unwrap();
+        assert_eq!(parsed.finalize(), ds.finalize());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |tag: &[u8]| DomainSeparator::<H>::try_from_bytes(tag).unwrap_err();
+        assert_eq!(parse(b"proto\xff"), ParseDomainSeparatorError::InvalidUtf8);
+        assert_eq!(
+            parse(b"proto\0A1x\0\0S1y"),
+            ParseDomainSeparatorError::EmptyOp { index: 1 }
+        );
+        assert_eq!(
+            parse(b"proto\0A1x\0"),
+            ParseDomainSeparatorError::EmptyOp { index: 1 }
+        );
+        assert_eq!(
+            parse(b"proto\0X1x"),
+            ParseDomainSeparatorError::UnknownOp { index: 0, id: 'X' }
+        );
+        for tag in [
+            &b"proto\0Ax"[..],
+            b"proto\0A0x",
+            b"proto\0S01x",
+            b"proto\0S99999999999999999999999x",
+        ] {
+            assert!(matches!(
+                parse(tag),
+                ParseDomainSeparatorError::InvalidCount { index: 0, .. }
+            ));
+        }
+        assert_eq!(
+            parse(b"proto\0A1x\0H0x"),
+            ParseDomainSeparatorError::InvalidCount { index: 1, id: 'H' }
+        );
+        assert_eq!(
+            parse(b"proto\0R1"),
+            ParseDomainSeparatorError::UnexpectedCount { index: 0, id: 'R' }
+        );
+        assert_eq!(
+            parse(b"proto\0Rx"),
+            ParseDomainSeparatorError::UnexpectedLabel { index: 0, id: 'R' }
+        );
+    }
+
+    #[test]
+    fn test_ops_yields_labels_without_merging() {
+        let ds = DomainSeparator::<H>::new("proto")
+            .absorb(1, "a")
+            .absorb(2, "b")
+            .hint("🦀")
+            .ratchet()
+            .squeeze(3, "c");
+        let ops = ds.ops().collect::<Vec<_>>();
+        assert_eq!(
+            ops,
+            vec![
+                (Op::Absorb(1), "a"),
+                (Op::Absorb(2), "b"),
+                (Op::Hint(None), "🦀"),
+                (Op::Ratchet, ""),
+                (Op::Squeeze(3), "c"),
+            ]
+        );
+    }
+
     #[test]
     fn test_simplify_stack_keeps_unlike_ops() {
         let tag = "test\0A2x\0S3y\0A1z";
@@ -516,7 +1266,7 @@ mod tests {
                 Op::Squeeze(4), // S2c + S2d
                 Op::Absorb(3),  // A3e
                 Op::Squeeze(1), // S1f
-                Op::Hint,       // Hd
+                Op::Hint(None), // Hd
             ]
         );
     }
@@ -525,7 +1275,23 @@ mod tests {
     fn test_hint_is_parsed_correctly() {
         let ds = DomainSeparator::<H>::new("hint_test").hint("my_hint");
         let ops = ds.finalize();
-        assert_eq!(ops, vec![Op::Hint]);
+        assert_eq!(ops, vec![Op::Hint(None)]);
+    }
+
+    #[test]
+    fn test_hint_bounded_round_trip() {
+        let ds = DomainSeparator::<H>::new("proto")
+            .hint_bounded(1024, "path")
+            .hint("h");
+        assert_eq!(ds.as_bytes(), b"proto\0H1024path\0Hh");
+        let parsed = DomainSeparator::<H>::try_from_bytes(ds.as_bytes()).unwrap();
+        assert_eq!(
+            parsed.ops().collect::<Vec<_>>(),
+            vec![(Op::Hint(Some(1024)), "path"), (Op::Hint(None), "h")]
+        );
+        assert_eq!(Op::Hint(Some(1024)).to_string(), "H1024");
+        assert!(ds.clone().try_hint_bounded(0, "empty").is_err());
+        assert!(ds.try_hint_bounded(1, "1label").is_err());
     }
 
     #[test]
@@ -541,6 +1307,152 @@ mod tests {
         let _ = DomainSeparator::<H>::new("x").hint("bad\0hint");
     }
 
+    #[test]
+    fn test_try_builders_return_errors() {
+        assert!(DomainSeparator::<H>::try_new("invalid\0session").is_err());
+        let ds = DomainSeparator::<H>::try_new("proto").unwrap();
+        assert!(ds.clone().try_absorb(0, "label").is_err());
+        assert!(ds.clone().try_absorb(1, "bad\0label").is_err());
+        assert!(ds.clone().try_absorb(1, "1label").is_err());
+        assert!(ds.clone().try_squeeze(0, "label").is_err());
+        assert!(ds.clone().try_squeeze(1, "bad\0label").is_err());
+        assert!(ds.clone().try_squeeze(1, "1label").is_err());
+        assert!(ds.clone().try_hint("bad\0hint").is_err());
+        assert!(ds.clone().try_hint("1hint").is_err());
+        assert!(ds.clone().try_add_bytes(0, "label").is_err());
+        assert!(ds.try_challenge_bytes(0, "label").is_err());
+    }
+
+    #[test]
+    fn test_try_builders_match_panicking_builders() {
+        let ds = DomainSeparator::<H>::try_new("proto")
+            .and_then(|ds| ds.try_absorb(2, "a"))
+            .and_then(|ds| ds.try_hint("h"))
+            .and_then(|ds| ds.try_squeeze(3, "c"))
+            .unwrap();
+        let expected = DomainSeparator::<H>::new("proto")
+            .absorb(2, "a")
+            .hint("h")
+            .squeeze(3, "c");
+        assert_eq!(ds.as_bytes(), expected.as_bytes());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_hint_label_starts_with_digit_panics() {
+        let _ = DomainSeparator::<H>::new("x").hint("1hint");
+    }
+
+    #[test]
+    fn test_absorb_dyn_is_not_merged() {
+        let ds = DomainSeparator::<H>::new("proto")
+            .absorb(1, "a")
+            .absorb_dyn("d")
+            .absorb_dyn("e")
+            .absorb(2, "b");
+        assert_eq!(ds.as_bytes(), b"proto\0A1a\0Dd\0De\0A2b");
+        assert_eq!(
+            ds.finalize(),
+            vec![Op::Absorb(1), Op::AbsorbDyn, Op::AbsorbDyn, Op::Absorb(2)]
+        );
+        let parsed = DomainSeparator::<H>::try_from_bytes(ds.as_bytes()).unwrap();
+        assert_eq!(parsed.finalize(), ds.finalize());
+        assert_eq!(ds.narg_size().dynamic, 2);
+        assert_eq!(
+            DomainSeparator::<H>::try_from_bytes(b"proto\0D3d").unwrap_err(),
+            ParseDomainSeparatorError::UnexpectedCount { index: 0, id: 'D' }
+        );
+        assert!(ds.try_absorb_dyn("1d").is_err());
+    }
+
+    #[test]
+    fn test_nest_and_repeat() {
+        let round = |ds: DomainSeparator<H>, _| ds.absorb(1, "msg").squeeze(1, "chal");
+        let sub = DomainSeparator::<H>::new("sub").repeat(2, "round", round);
+        let ds = DomainSeparator::<H>::new("proto")
+            .absorb(1, "x")
+            .nest("outer", sub)
+            .absorb(2, "y");
+        assert_eq!(
+            ds.as_bytes(),
+            b"proto\0A1x\0(outer\0(round[0]\0A1msg\0S1chal\0)\0(round[1]\0A1msg\0S1chal\0)\0)\0A2y"
+        );
+        assert_eq!(
+            ds.paths().map(|(_, path)| path).collect::<Vec<_>>(),
+            [
+                "x",
+                "outer/round[0]/msg",
+                "outer/round[0]/chal",
+                "outer/round[1]/msg",
+                "outer/round[1]/chal",
+                "y"
+            ]
+        );
+        // nesting is transparent to the sponge
+        assert_eq!(
+            ds.finalize(),
+            vec![
+                Op::Absorb(2),
+                Op::Squeeze(1),
+                Op::Absorb(1),
+                Op::Squeeze(1),
+                Op::Absorb(2)
+            ]
+        );
+        let parsed = DomainSeparator::<H>::try_from_bytes(ds.as_bytes()).unwrap();
+        assert_eq!(parsed.as_bytes(), ds.as_bytes());
+
+        // the same operations, nested differently, give different domain separators
+        let flat = DomainSeparator::<H>::new("proto").repeat(2, "round", round);
+        let nested = DomainSeparator::<H>::new("proto")
+            .nest("round[0]", round(DomainSeparator::new("sub"), 0))
+            .nest("round[1]", round(DomainSeparator::new("sub"), 1));
+        assert_eq!(flat.as_bytes(), nested.as_bytes());
+        assert_ne!(
+            flat.as_bytes(),
+            DomainSeparator::<H>::new("proto")
+                .absorb(1, "round[0]/msg")
+                .squeeze(1, "round[0]/chal")
+                .absorb(1, "round[1]/msg")
+                .squeeze(1, "round[1]/chal")
+                .as_bytes()
+        );
+
+        assert!(DomainSeparator::<H>::new("proto")
+            .try_nest("1st", DomainSeparator::new("sub"))
+            .is_err());
+        assert!(DomainSeparator::<H>::new("proto")
+            .try_repeat(2, "round", |ds, _| ds.try_absorb(0, "x"))
+            .is_err());
+        assert_eq!(
+            DomainSeparator::<H>::new("proto")
+                .repeat(0, "round", round)
+                .as_bytes(),
+            b"proto"
+        );
+    }
+
+    #[test]
+    fn test_parse_unbalanced_nesting() {
+        let parse = |tag: &[u8]| DomainSeparator::<H>::try_from_bytes(tag).unwrap_err();
+        assert_eq!(
+            parse(b"proto\0(a\0A1x\0)\0)"),
+            ParseDomainSeparatorError::UnmatchedEnd { index: 3 }
+        );
+        assert_eq!(
+            parse(b"proto\0(a\0(b\0A1x\0)"),
+            ParseDomainSeparatorError::UnclosedNest { index: 0 }
+        );
+        assert_eq!(
+            parse(b"proto\0(a\0)x"),
+            ParseDomainSeparatorError::UnexpectedLabel { index: 1, id: ')' }
+        );
+        assert_eq!(
+            parse(b"proto\0(2a\0)"),
+            ParseDomainSeparatorError::UnexpectedCount { index: 0, id: '(' }
+        );
+    }
+
     #[test]
     fn test_hint_combined_with_absorb_and_squeeze() {
         let ds = DomainSeparator::<H>::new("combo")
@@ -548,6 +1460,6 @@ mod tests {
             .hint("meta")
             .squeeze(2, "y");
         let ops = ds.finalize();
-        assert_eq!(ops, vec![Op::Absorb(1), Op::Hint, Op::Squeeze(2)]);
+        assert_eq!(ops, vec![Op::Absorb(1), Op::Hint(None), Op::Squeeze(2)]);
     }
 }
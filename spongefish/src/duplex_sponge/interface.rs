@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use super::Unit;
 
 /// A [`DuplexInterface`] is an abstract interface for absorbing and squeezing data.
@@ -31,20 +33,37 @@ where
     /// The resulting state is compressed.
     fn ratchet_unchecked(&mut self) -> &mut Self;
 
-    // /// Exports the hash state, allowing for preprocessing.
-    // ///
-    // /// This function can be used for duplicating the state of the sponge,
-    // /// but is limited to exporting the state in a way that is compatible
-    // /// with the `load` function.
-    // fn tag(self) -> &'static [Self::U];
+    /// Exports the hash state, allowing for preprocessing.
+    ///
+    /// This function is meant to be called right after [`DuplexSpongeInterface::ratchet_unchecked`],
+    /// when the state is compressed and holds no information in the rate.
+    /// The exported tag is compatible with the [`DuplexSpongeInterface::load`] function.
+    ///
+    /// The default implementation returns an empty tag,
+    /// signalling that the sponge does not support preprocessing.
+    fn tag(&self) -> Vec<U> {
+        Vec::new()
+    }
+
+    /// Loads a hash state previously exported with [`DuplexSpongeInterface::tag`].
+    ///
+    /// Returns `None` if the tag is malformed, or if the sponge does not support preprocessing
+    /// (as in the default implementation).
+    fn load(_tag: &[U]) -> Option<Self> {
+        None
+    }
 }
 
 impl Unit for u8 {
-    fn write(bunch: &[Self], w: &mut impl std::io::Write) -> Result<(), std::io::Error> {
+    fn write(bunch: &[Self], w: &mut impl crate::io::Write) -> Result<(), crate::io::Error> {
         w.write_all(bunch)
     }
 
-    fn read(r: &mut impl std::io::Read, bunch: &mut [Self]) -> Result<(), std::io::Error> {
+    fn read(r: &mut impl crate::io::Read, bunch: &mut [Self]) -> Result<(), crate::io::Error> {
         r.read_exact(bunch)
     }
+
+    fn encoded_size() -> usize {
+        1
+    }
 }
@@ -16,6 +16,8 @@
 //! `squeeze_unchecked` will use the squeeze oracle to output `output.len()` bytes,
 //! and finally `squeeze_end` will set the state `cv` to the current squeeze digest and length.
 //!
+use alloc::vec::Vec;
+
 use digest::{
     core_api::BlockSizeUser, crypto_common::generic_array::GenericArray, typenum::Unsigned, Digest,
     FixedOutputReset, Reset,
@@ -188,6 +190,19 @@ impl<D: BlockSizeUser + Digest + Clone + FixedOutputReset> DuplexSpongeInterface
             unreachable!()
         }
     }
+
+    fn tag(&self) -> Vec<u8> {
+        self.cv.to_vec()
+    }
+
+    fn load(tag: &[u8]) -> Option<Self> {
+        if tag.len() != Self::DIGEST_SIZE {
+            return None;
+        }
+        let mut bridge = Self::default();
+        bridge.cv.copy_from_slice(tag);
+        Some(bridge)
+    }
 }
 
 #[test]
@@ -12,6 +12,8 @@ mod interface;
 /// Legacy hash functions support (e.g. [`sha2`](https://crates.io/crates/sha2), [`blake2`](https://crates.io/crates/blake2)).
 pub mod legacy;
 
+use alloc::vec::Vec;
+
 pub use interface::DuplexSpongeInterface;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
@@ -21,9 +23,26 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 /// and that we can zeroize them.
 pub trait Unit: Clone + Sized + zeroize::Zeroize {
     /// Write a bunch of units in the wire.
-    fn write(bunch: &[Self], w: &mut impl std::io::Write) -> Result<(), std::io::Error>;
+    fn write(bunch: &[Self], w: &mut impl crate::io::Write) -> Result<(), crate::io::Error>;
     /// Read a bunch of units from the wire
-    fn read(r: &mut impl std::io::Read, bunch: &mut [Self]) -> Result<(), std::io::Error>;
+    fn read(r: &mut impl crate::io::Read, bunch: &mut [Self]) -> Result<(), crate::io::Error>;
+    /// The number of bytes taken by one unit on the wire.
+    ///
+    /// The default implementation writes [`Default::default`] with [`Unit::write`] and measures it:
+    /// implementations can override it with a cheaper computation.
+    ///
+    /// # Panics
+    ///
+    /// The default implementation panics if [`Unit::write`] fails on an in-memory buffer.
+    #[must_use]
+    fn encoded_size() -> usize
+    where
+        Self: Default,
+    {
+        let mut buf = Vec::new();
+        Self::write(&[Self::default()], &mut buf).expect("Units can be written to memory");
+        buf.len()
+    }
 }
 
 /// The basic state of a cryptographic sponge.
@@ -121,10 +140,6 @@ impl<U: Unit, C: Permutation<U = U>> DuplexSpongeInterface<U> for DuplexSponge<C
         self.squeeze_unchecked(rest)
     }
 
-    // fn tag(self) -> &'static [Self::U] {
-    //     &self.state[C::RATE..]
-    // }
-
     fn ratchet_unchecked(&mut self) -> &mut Self {
         self.permutation.permute();
         // set to zero the state up to rate
@@ -135,6 +150,28 @@ impl<U: Unit, C: Permutation<U = U>> DuplexSpongeInterface<U> for DuplexSponge<C
         self.squeeze_pos = C::R;
         self
     }
+
+    /// The tag is the state from the absorb position on: the (zeroed) end of the rate and the capacity.
+    ///
+    /// Absorption resumes where it stopped before the ratchet,
+    /// so the length of the tag encodes the absorb position.
+    fn tag(&self) -> Vec<U> {
+        self.permutation.as_ref()[self.absorb_pos..].to_vec()
+    }
+
+    fn load(tag: &[U]) -> Option<Self> {
+        if tag.len() < C::N - C::R || tag.len() > C::N {
+            return None;
+        }
+        let absorb_pos = C::N - tag.len();
+        let mut permutation = C::default();
+        permutation.as_mut()[absorb_pos..].clone_from_slice(tag);
+        Some(Self {
+            permutation,
+            absorb_pos,
+            squeeze_pos: C::R,
+        })
+    }
 }
 
 #[cfg(test)]
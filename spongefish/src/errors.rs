@@ -7,8 +7,9 @@
 ///
 /// - Serialization/Deseralization errors ([`ProofError::SerializationError`]):
 ///   This includes all potential problems when extracting a particular type from sequences of bytes.
+///   The underlying error, if any, is available as [`Error::source`].
 ///
-/// - Invalid Proof format ([`ProofError::InvalidIO`]):
+/// - Invalid Proof format ([`ProofError::InvalidDomainSeparator`]):
 ///   At a higher level, a proof object have to respect the same length and the same types as the protocol description.
 ///   This error is a wrapper under the [`DomainSeparatorMismatch`] and provides convenient dereference/conversion implementations for
 ///   moving from/to an [`DomainSeparatorMismatch`].
@@ -17,11 +18,77 @@
 ///   An error to signal that the verification equation has failed. Destined for end users.
 ///
 /// A [`core::Result::Result`] wrapper called [`ProofResult`] (having error fixed to [`ProofError`]) is also provided.
-use std::{borrow::Borrow, error::Error, fmt::Display};
+use alloc::{
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::{borrow::Borrow, error::Error, fmt::Display};
+
+use crate::{domain_separator::Op, pattern::TranscriptError};
 
 /// Signals a domain separator is inconsistent with the description provided.
+///
+/// The `index` of each variant is the position of the operation in the domain separator
+/// (starting from zero, not counting the session identifier).
+/// Consecutive absorb (resp. squeeze) operations are merged, and reported with the
+/// position and label of the first one.
 #[derive(Debug, Clone)]
-pub struct DomainSeparatorMismatch(String);
+pub enum DomainSeparatorMismatch {
+    /// The operation attempted is not the one expected by the domain separator.
+    UnexpectedOp {
+        index: usize,
+        expected: Op,
+        label: String,
+        got: Op,
+    },
+    /// The operation attempted is past the end of the domain separator.
+    Exhausted { got: Op },
+    /// Some operations of the domain separator have not been performed.
+    Unfinished {
+        index: usize,
+        label: String,
+        remaining: Vec<Op>,
+    },
+    /// A hint is longer than the bound set in the domain separator.
+    HintTooLong {
+        index: usize,
+        label: String,
+        max_len: usize,
+        len: usize,
+    },
+    /// Reading from (or writing to) the NARG string failed.
+    Io(Arc<crate::io::Error>),
+    /// The interaction attempted does not follow the [`InteractionPattern`](crate::pattern::InteractionPattern).
+    Pattern(TranscriptError),
+    /// Any other inconsistency.
+    Other(String),
+}
+
+/// Signals a domain separator string is malformed.
+///
+/// The `index` of each variant is the position of the offending operation
+/// (starting from zero, not counting the session identifier).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseDomainSeparatorError {
+    /// The domain separator is not valid UTF-8.
+    InvalidUtf8,
+    /// An operation is empty, e.g. because of two consecutive separators.
+    EmptyOp { index: usize },
+    /// The operation identifier is not one of `A`, `D`, `S`, `H`, `R`, `(`, `)`.
+    UnknownOp { index: usize, id: char },
+    /// The count is missing, zero, has leading zeros, or overflows.
+    InvalidCount { index: usize, id: char },
+    /// The operation does not take a count, but one was provided
+    /// (or the label starts with a digit).
+    UnexpectedCount { index: usize, id: char },
+    /// The operation does not take a label, but one was provided.
+    UnexpectedLabel { index: usize, id: char },
+    /// A nested sub-protocol is closed, but none is open.
+    UnmatchedEnd { index: usize },
+    /// A nested sub-protocol is opened, but never closed.
+    UnclosedNest { index: usize },
+}
 
 /// An error happened when creating or verifying a proof.
 #[derive(Debug, Clone)]
@@ -31,30 +98,114 @@ pub enum ProofError {
     /// The domain separator specified mismatches the protocol execution.
     InvalidDomainSeparator(DomainSeparatorMismatch),
     /// Serialization/Deserialization led to errors.
-    SerializationError,
+    ///
+    /// Holds the error raised by the serialization library, when there is one.
+    SerializationError(Option<Arc<dyn Error + Send + Sync>>),
 }
 
 /// The result type when trying to prove or verify a proof using Fiat-Shamir.
 pub type ProofResult<T> = Result<T, ProofError>;
 
+impl ProofError {
+    /// A serialization error caused by `err`.
+    pub fn serialization(err: impl Error + Send + Sync + 'static) -> Self {
+        Self::SerializationError(Some(Arc::new(err)))
+    }
+}
+
 impl Display for DomainSeparatorMismatch {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self.0)
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnexpectedOp {
+                index,
+                expected,
+                label,
+                got,
+            } => write!(f, "Expected {expected} '{label}' at op #{index}, got {got}"),
+            Self::Exhausted { got } => {
+                write!(f, "Got {got}, but all operations have been performed")
+            }
+            Self::Unfinished {
+                index,
+                label,
+                remaining,
+            } => {
+                write!(f, "Unfinished operations from '{label}' at op #{index}:")?;
+                remaining.iter().try_for_each(|op| write!(f, " {op}"))
+            }
+            Self::HintTooLong {
+                index,
+                label,
+                max_len,
+                len,
+            } => write!(
+                f,
+                "Hint '{label}' at op #{index} has {len} bytes, more than the bound of {max_len}"
+            ),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::Pattern(e) => write!(f, "{e}"),
+            Self::Other(s) => write!(f, "{s}"),
+        }
     }
 }
 
 impl Display for ProofError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
-            Self::SerializationError => write!(f, "Serialization Error"),
+            Self::SerializationError(None) => write!(f, "Serialization Error"),
+            Self::SerializationError(Some(e)) => write!(f, "Serialization Error: {e}"),
             Self::InvalidDomainSeparator(e) => e.fmt(f),
             Self::InvalidProof => write!(f, "Invalid proof"),
         }
     }
 }
 
-impl Error for DomainSeparatorMismatch {}
-impl Error for ProofError {}
+impl Display for ParseDomainSeparatorError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidUtf8 => write!(f, "Domain separator is not valid UTF-8"),
+            Self::EmptyOp { index } => write!(f, "Empty operation at op #{index}"),
+            Self::UnknownOp { index, id } => write!(f, "Unknown operation '{id}' at op #{index}"),
+            Self::InvalidCount { index, id } => {
+                write!(f, "Invalid count for operation '{id}' at op #{index}")
+            }
+            Self::UnexpectedCount { index, id } => {
+                write!(f, "Unexpected count for operation '{id}' at op #{index}")
+            }
+            Self::UnexpectedLabel { index, id } => {
+                write!(f, "Unexpected label for operation '{id}' at op #{index}")
+            }
+            Self::UnmatchedEnd { index } => {
+                write!(f, "Unmatched end of sub-protocol at op #{index}")
+            }
+            Self::UnclosedNest { index } => {
+                write!(f, "Sub-protocol opened at op #{index} is never closed")
+            }
+        }
+    }
+}
+
+impl Error for DomainSeparatorMismatch {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e.as_ref()),
+            Self::Pattern(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Error for ParseDomainSeparatorError {}
+
+impl Error for ProofError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidDomainSeparator(e) => Some(e),
+            Self::SerializationError(Some(e)) => Some(e.as_ref()),
+            Self::SerializationError(None) | Self::InvalidProof => None,
+        }
+    }
+}
 
 impl From<&str> for DomainSeparatorMismatch {
     fn from(s: &str) -> Self {
@@ -64,7 +215,7 @@ impl From<&str> for DomainSeparatorMismatch {
 
 impl From<String> for DomainSeparatorMismatch {
     fn from(s: String) -> Self {
-        Self(s)
+        Self::Other(s)
     }
 }
 
@@ -74,8 +225,20 @@ impl<B: Borrow<DomainSeparatorMismatch>> From<B> for ProofError {
     }
 }
 
-impl From<std::io::Error> for DomainSeparatorMismatch {
-    fn from(value: std::io::Error) -> Self {
-        Self(value.to_string())
+impl From<TranscriptError> for DomainSeparatorMismatch {
+    fn from(value: TranscriptError) -> Self {
+        Self::Pattern(value)
+    }
+}
+
+impl From<TranscriptError> for ProofError {
+    fn from(value: TranscriptError) -> Self {
+        Self::InvalidDomainSeparator(value.into())
+    }
+}
+
+impl From<crate::io::Error> for DomainSeparatorMismatch {
+    fn from(value: crate::io::Error) -> Self {
+        Self::Io(Arc::new(value))
     }
 }
@@ -0,0 +1,116 @@
+//! The reading and writing interface used by [`Unit`](crate::Unit) to encode prover messages.
+//!
+//! With the `std` feature, this is just a re-export of [`std::io::Read`], [`std::io::Write`], [`std::io::Error`] and [`std::io::ErrorKind`].
+//! Without it, the module provides a minimal drop-in replacement
+//! implemented for byte slices and vectors, which is all the NARG string needs.
+
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind, Read, Write};
+
+#[cfg(not(feature = "std"))]
+pub use no_std_io::{Error, ErrorKind, Read, Write};
+
+#[cfg(not(feature = "std"))]
+mod no_std_io {
+    use alloc::{boxed::Box, vec::Vec};
+    use core::fmt;
+
+    /// An I/O error, mirroring the subset of [`std::io::Error`] used by spongefish.
+    #[derive(Debug)]
+    pub enum Error {
+        /// The reader ran out of bytes before filling the buffer.
+        UnexpectedEof,
+        /// Any other error, e.g. an encoding failure.
+        Other(Box<dyn core::error::Error + Send + Sync>),
+    }
+
+    /// The kind of an [`Error`], mirroring the subset of [`std::io::ErrorKind`] used by spongefish.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum ErrorKind {
+        /// See [`Error::UnexpectedEof`].
+        UnexpectedEof,
+        /// See [`Error::Other`].
+        Other,
+    }
+
+    impl Error {
+        /// The kind of this error.
+        #[must_use]
+        pub const fn kind(&self) -> ErrorKind {
+            match self {
+                Self::UnexpectedEof => ErrorKind::UnexpectedEof,
+                Self::Other(_) => ErrorKind::Other,
+            }
+        }
+
+        /// Create an error out of an arbitrary error payload, as [`std::io::Error::other`] does.
+        pub fn other<E>(error: E) -> Self
+        where
+            E: Into<Box<dyn core::error::Error + Send + Sync>>,
+        {
+            Self::Other(error.into())
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::UnexpectedEof => write!(f, "failed to fill whole buffer"),
+                Self::Other(e) => e.fmt(f),
+            }
+        }
+    }
+
+    impl core::error::Error for Error {
+        fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+            match self {
+                Self::UnexpectedEof => None,
+                Self::Other(e) => e.source(),
+            }
+        }
+    }
+
+    /// Read bytes out of a source, as [`std::io::Read::read_exact`] does.
+    pub trait Read {
+        /// Read exactly `buf.len()` bytes, or fail with [`Error::UnexpectedEof`].
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error>;
+    }
+
+    /// Write bytes into a sink, as [`std::io::Write::write_all`] does.
+    pub trait Write {
+        /// Write all of `buf` into the sink.
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), Error>;
+    }
+
+    impl Read for &[u8] {
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+            if buf.len() > self.len() {
+                *self = &self[self.len()..];
+                return Err(Error::UnexpectedEof);
+            }
+            let (head, tail) = self.split_at(buf.len());
+            buf.copy_from_slice(head);
+            *self = tail;
+            Ok(())
+        }
+    }
+
+    impl<R: Read + ?Sized> Read for &mut R {
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+            (**self).read_exact(buf)
+        }
+    }
+
+    impl Write for Vec<u8> {
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+            self.extend_from_slice(buf);
+            Ok(())
+        }
+    }
+
+    impl<W: Write + ?Sized> Write for &mut W {
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+            (**self).write_all(buf)
+        }
+    }
+}
@@ -2,7 +2,7 @@
 //! Despite internally we use the same permutation function,
 //! we build a duplex sponge in overwrite mode
 //! on the top of it using the `DuplexSponge` trait.
-use std::fmt::Debug;
+use core::fmt::Debug;
 
 use zerocopy::IntoBytes;
 use zeroize::{Zeroize, ZeroizeOnDrop};
@@ -20,6 +20,9 @@ pub type Keccak = DuplexSponge<KeccakF1600>;
 
 /// Keccak permutation internal state: 25 64-bit words,
 /// or equivalently 200 bytes in little-endian order.
+///
+/// The words are stored little-endian regardless of the target,
+/// so that the byte view of the state is the same on every platform.
 #[derive(Clone, PartialEq, Eq, Default, Zeroize, ZeroizeOnDrop)]
 pub struct KeccakF1600([u64; 25]);
 
@@ -35,7 +38,20 @@ impl Permutation for KeccakF1600 {
     }
 
     fn permute(&mut self) {
-        keccak::f1600(&mut self.0);
+        // The conversion is a no-op on little-endian targets.
+        permute_lanes(&mut self.0, u64::from_le);
+    }
+}
+
+/// Apply [`keccak::f1600`] to lanes stored in memory,
+/// where `from_le` converts a stored lane to its value, and back.
+fn permute_lanes(lanes: &mut [u64; 25], from_le: fn(u64) -> u64) {
+    for lane in lanes.iter_mut() {
+        *lane = from_le(*lane);
+    }
+    keccak::f1600(lanes);
+    for lane in lanes.iter_mut() {
+        *lane = from_le(*lane);
     }
 }
 
@@ -53,9 +69,67 @@ impl AsMut<[u8]> for KeccakF1600 {
 
 /// Censored version of Debug
 impl Debug for KeccakF1600 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("AlignedKeccakF1600")
             .field(&"<redacted>")
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_are_little_endian_lanes() {
+        let mut state = KeccakF1600::default();
+        state.as_mut()[..8].copy_from_slice(&0x0102_0304_0506_0708u64.to_le_bytes());
+        assert_eq!(u64::from_le(state.0[0]), 0x0102_0304_0506_0708);
+
+        // First lane of Keccak-f[1600] applied to the all-zero state.
+        let mut state = KeccakF1600::default();
+        state.permute();
+        assert_eq!(state.as_ref()[..8], 0xF125_8F79_40E1_DDE7u64.to_le_bytes());
+    }
+
+    /// Permute the state `bytes` as a target storing lanes with `to_bytes` would.
+    fn permute_as(
+        bytes: &[u8],
+        from_bytes: fn([u8; 8]) -> u64,
+        to_bytes: fn(u64) -> [u8; 8],
+        from_le: fn(u64) -> u64,
+    ) -> [u8; 200] {
+        let mut lanes = [0u64; 25];
+        for (lane, chunk) in lanes.iter_mut().zip(bytes.chunks_exact(8)) {
+            *lane = from_bytes(chunk.try_into().unwrap());
+        }
+        permute_lanes(&mut lanes, from_le);
+        let mut output = [0u8; 200];
+        for (chunk, lane) in output.chunks_exact_mut(8).zip(lanes) {
+            chunk.copy_from_slice(&to_bytes(lane));
+        }
+        output
+    }
+
+    #[test]
+    fn test_permutation_is_independent_of_the_byte_order() {
+        // A state with distinct lanes: the all-zero state permuted once.
+        let mut state = KeccakF1600::default();
+        state.permute();
+        let input: [u8; 200] = state.as_ref().try_into().unwrap();
+        state.permute();
+        // First lane of Keccak-f[1600] applied twice to the all-zero state.
+        assert_eq!(state.as_ref()[..8], 0x2D5C_954D_F96E_CB3Cu64.to_le_bytes());
+
+        // Mock little- and big-endian targets: the latter byte-swaps the lanes it loads.
+        let little_endian = permute_as(&input, u64::from_le_bytes, u64::to_le_bytes, |lane| lane);
+        let big_endian = permute_as(
+            &input,
+            u64::from_be_bytes,
+            u64::to_be_bytes,
+            u64::swap_bytes,
+        );
+        assert_eq!(little_endian, state.as_ref());
+        assert_eq!(big_endian, state.as_ref());
+    }
+}
@@ -38,6 +38,8 @@
 //! An [`DomainSeparator`] is a UTF8-encoded string wrapper. Absorptions are marked by `A` and
 //! squeezes by `S`, followed by the respective length
 //! (note: length is expressed in terms of [`Unit`], native elements over which the hash function works).
+//! Prover messages whose length is only known at runtime are marked by `D`, without length:
+//! their length is absorbed (and written in the NARG string) before them.
 //! A label is added at the end of each absorb/squeeze, to describe the *type* and
 //! *the variable* as used in the protocol. Operations are separated by a NULL byte and therefore labels cannot contain
 //! NULL bytes themselves, nor they can start with an ASCII digit.
@@ -46,6 +48,7 @@
 //! The library comes with support for algebraic objects over arkworks and zkcrypto:
 //! - with feature flag `--feature=ark`, the module [`codecs::arkworks_algebra`] provides extension traits for arkworks fields and groups;
 //! - with feature flag `--feature=group`, the module [`codecs::zkcrypto_group`] provides extension traits for zkcrypto's field and group traits.
+//! - with feature flag `--feature=serde`, [`pattern::InteractionPattern`] can be (de)serialized, e.g. as JSON.
 //! See the [`codecs`] module for more information.
 //!
 //!
@@ -118,13 +121,16 @@
 //! [SAFE]: https://eprint.iacr.org/2023/522
 //! [Merlin]: https://github.com/dalek-cryptography/merlin
 //! [`digest::Digest`]: https://docs.rs/digest/latest/digest/trait.Digest.html
+//!
+//! # `no_std` support
+//!
+//! The crate builds under `#![no_std]` (with `alloc`) when the default `std` feature is disabled.
+//! Without `std`, [`DefaultRng`] is not available and the prover's random number generator must be passed explicitly
+//! (and named in the type of [`ProverState`]),
+//! and there is no process-wide [`UnfinishedPolicy`]: unfinished states are dropped silently.
+#![cfg_attr(not(feature = "std"), no_std)]
 
-#[cfg(target_endian = "big")]
-compile_error!(
-    r#"
-This crate doesn't support big-endian targets.
-"#
-);
+extern crate alloc;
 
 /// Hash functions traits and implementations.
 pub mod duplex_sponge;
@@ -133,11 +139,16 @@ mod errors;
 /// Verifier state and transcript deserialization.
 mod verifier;
 
+/// Minimal I/O traits, re-exported from `std::io` when available.
+pub mod io;
+
 /// Built-in permutation functions.
 pub mod keccak;
 
 /// APIs for common zkp libraries.
 pub mod codecs;
+/// Diagrams of protocols.
+mod diagram;
 /// domain separator
 mod domain_separator;
 /// Prover's internal state and transcript generation.
@@ -154,15 +165,20 @@ pub mod pattern;
 /// Traits for byte support.
 pub mod traits;
 
-pub use domain_separator::DomainSeparator;
+pub use domain_separator::{DomainSeparator, NargSize, Op};
 pub use duplex_sponge::{legacy::DigestBridge, DuplexSpongeInterface, Unit};
-pub use errors::{DomainSeparatorMismatch, ProofError, ProofResult};
-pub use prover::ProverState;
-pub use sho::HashStateWithInstructions;
+pub use errors::{DomainSeparatorMismatch, ParseDomainSeparatorError, ProofError, ProofResult};
+pub use prover::{DeterministicRng, ProverState, ReseedPolicy};
+#[cfg(feature = "std")]
+pub use sho::{set_unfinished_policy, unfinished_policy};
+pub use sho::{
+    CompiledDomainSeparator, HashStateWithInstructions, PreprocessedState, UnfinishedPolicy,
+};
 pub use traits::*;
-pub use verifier::VerifierState;
+pub use verifier::{NargReader, NargSource, VerifierState};
 
 /// Default random number generator used ([`rand::rngs::OsRng`]).
+#[cfg(feature = "std")]
 pub type DefaultRng = rand::rngs::OsRng;
 
 /// Default hash function used ([`keccak::Keccak`]).
@@ -1,7 +1,11 @@
-use core::{any::type_name, fmt::Display};
+use alloc::borrow::Cow;
+use core::fmt::Display;
+
+use super::TranscriptType;
 
 /// A single abstract prover-verifier interaction.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Interaction {
     /// Hierarchical nesting of the interactions.
     hierarchy: Hierarchy,
@@ -9,22 +13,28 @@ pub struct Interaction {
     kind: Kind,
     /// A label identifying the purpose of the value.
     label: Label,
-    /// The Rust name of the type of the value.
+    /// The stable identifier of the type of the value, see [`TranscriptType`].
     ///
-    /// We use [`core::any::type_name`] to verify value types instead of [`core::any::TypeID`] since
-    /// the latter only supports types with a `'static` lifetime. The downside of `type_name` is
-    /// that it is slightly less precise in that it can create more type collisions. But this is
-    /// acceptable here as it only serves as an additional check and as debug information.
-    type_name: &'static str,
+    /// Type identifiers are not part of the stable text format, so parsed interactions have none
+    /// and match values of any type.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    type_id: Option<Cow<'static, str>>,
     /// Length of the value.
     length: Length,
 }
 
 /// Labels for interactions.
-pub type Label = &'static str;
+///
+/// Labels are either static or built at runtime, e.g. `format!("round-{i}")`.
+/// Methods taking labels accept any `impl Into<Label>`, such as `&'static str` and `String`.
+pub type Label = Cow<'static, str>;
 
 /// Kinds of prover-verifier interactions
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Kind {
     /// A protocol containing mixed interactions.
     Protocol,
@@ -40,6 +50,7 @@ pub enum Kind {
 
 /// Kinds of prover-verifier interactions
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Hierarchy {
     /// A single interaction.
     Atomic,
@@ -51,6 +62,7 @@ pub enum Hierarchy {
 
 /// Length of values involved in interactions.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Length {
     /// No length information.
     None,
@@ -60,17 +72,62 @@ pub enum Length {
     Fixed(usize),
     /// A dynamic number of values.
     Dynamic,
+    /// A dynamic number of values, with the number recorded at runtime.
+    ///
+    /// Playing back a [`Length::Dynamic`] interaction accepts any runtime length,
+    /// while a recorded one is checked.
+    Runtime(usize),
 }
 
 impl Interaction {
     #[must_use]
-    pub fn new<T: ?Sized>(hierarchy: Hierarchy, kind: Kind, label: Label, length: Length) -> Self {
-        let type_name = type_name::<T>();
+    pub fn new<T: TranscriptType + ?Sized>(
+        hierarchy: Hierarchy,
+        kind: Kind,
+        label: impl Into<Label>,
+        length: Length,
+    ) -> Self {
+        Self::with_type_id(hierarchy, kind, label, length, T::type_id())
+    }
+
+    /// Create an interaction with an explicit type identifier,
+    /// e.g. the one of a [`TranscriptType`] in another family than `()`.
+    #[must_use]
+    pub fn with_type_id(
+        hierarchy: Hierarchy,
+        kind: Kind,
+        label: impl Into<Label>,
+        length: Length,
+        type_id: Cow<'static, str>,
+    ) -> Self {
+        Self {
+            hierarchy,
+            kind,
+            label: label.into(),
+            type_id: Some(type_id),
+            length,
+        }
+    }
+
+    /// The same interaction, with another length.
+    #[must_use]
+    pub(super) fn with_length(self, length: Length) -> Self {
+        Self { length, ..self }
+    }
+
+    /// Create an interaction without type information, as parsed from the text format.
+    #[must_use]
+    pub(super) const fn untyped(
+        hierarchy: Hierarchy,
+        kind: Kind,
+        label: Label,
+        length: Length,
+    ) -> Self {
         Self {
             hierarchy,
             kind,
             label,
-            type_name,
+            type_id: None,
             length,
         }
     }
@@ -85,6 +142,37 @@ impl Interaction {
         self.kind
     }
 
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)] // False positive
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    #[must_use]
+    pub const fn length(&self) -> Length {
+        self.length
+    }
+
+    /// The identifier of the type of the value, if known.
+    #[must_use]
+    pub fn type_id(&self) -> Option<&str> {
+        self.type_id.as_deref()
+    }
+
+    /// Returns `true` if `other` is an occurrence of this expected interaction.
+    ///
+    /// Interactions without type information match values of any type,
+    /// and [`Length::Dynamic`] matches any [`Length::Runtime`].
+    #[must_use]
+    pub(super) fn matches(&self, other: &Self) -> bool {
+        self.hierarchy == other.hierarchy
+            && self.kind == other.kind
+            && self.label == other.label
+            && (self.length == other.length
+                || (self.length == Length::Dynamic && matches!(other.length, Length::Runtime(_))))
+            && (self.type_id.is_none() || self.type_id == other.type_id)
+    }
+
     /// Returns `true` if this is a `Hierarchy::End` that closes the provided
     /// `Hierarchy::Begin`.
     #[must_use]
@@ -93,32 +181,35 @@ impl Interaction {
             && other.hierarchy == Hierarchy::Begin
             && self.kind == other.kind
             && self.label == other.label
-            && self.type_name == other.type_name
+            && self.type_id == other.type_id
             && self.length == other.length
     }
 }
 
 impl Display for Interaction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         if f.alternate() {
             // Domain separator mode: stable unambiguous format.
             write!(f, "{} {}", self.hierarchy, self.kind)?;
             // Length prefixed strings for labels to disambiguate
             write!(f, " {} {}", self.label.len(), self.label)?;
             write!(f, " {}", self.length)
-            // Leave out type names for domain separators.
+            // Leave out type identifiers for domain separators.
         } else {
             write!(
                 f,
-                "{} {} {} {} {}",
-                self.hierarchy, self.kind, self.label, self.length, self.type_name,
-            )
+                "{} {} {} {}",
+                self.hierarchy, self.kind, self.label, self.length,
+            )?;
+            self.type_id
+                .as_ref()
+                .map_or(Ok(()), |type_id| write!(f, " {type_id}"))
         }
     }
 }
 
 impl Display for Hierarchy {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Atomic => write!(f, "Atomic"),
             Self::Begin => write!(f, "Begin"),
@@ -128,7 +219,7 @@ impl Display for Hierarchy {
 }
 
 impl Display for Kind {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Protocol => write!(f, "Protocol"),
             Self::Public => write!(f, "Public"),
@@ -140,12 +231,13 @@ impl Display for Kind {
 }
 
 impl Display for Length {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::None => write!(f, "None"),
             Self::Scalar => write!(f, "Scalar"),
             Self::Fixed(size) => write!(f, "Fixed({size})"),
             Self::Dynamic => write!(f, "Dynamic"),
+            Self::Runtime(size) => write!(f, "Runtime({size})"),
         }
     }
 }
@@ -156,7 +248,7 @@ mod tests {
 
     #[test]
     fn test_domain_separator() {
-        let interaction = Interaction::new::<Vec<f64>>(
+        let interaction = Interaction::new::<[u64]>(
             Hierarchy::Atomic,
             Kind::Message,
             "test-message",
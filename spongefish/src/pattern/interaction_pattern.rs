@@ -1,38 +1,84 @@
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::fmt::Display;
 
 use thiserror::Error;
 
-use super::{interaction::Hierarchy, Interaction, Kind};
+use super::{interaction::Hierarchy, Interaction, Kind, Length};
+use crate::diagram::{Diagram, Step};
 
 /// Abstract transcript containing prover-verifier interactions
+///
+/// With the `serde` feature, it is (de)serialized as the sequence of its interactions,
+/// including the type identifiers that the text format leaves out.
 #[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "Vec<Interaction>", into = "Vec<Interaction>")
+)]
 pub struct InteractionPattern {
     interactions: Vec<Interaction>,
 }
 
-/// Errors when validating a transcript.
+/// Errors when recording, validating or playing back a transcript.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Error)]
 pub enum TranscriptError {
     #[error("Missing Begin for {end} at {position}")]
-    MissingBegin { position: usize, end: Interaction },
+    MissingBegin {
+        position: usize,
+        end: Box<Interaction>,
+    },
     #[error(
         "Invalid kind {interaction} at {interaction_position} for {begin} at {begin_position}"
     )]
     InvalidKind {
         begin_position: usize,
-        begin: Interaction,
+        begin: Box<Interaction>,
         interaction_position: usize,
-        interaction: Interaction,
+        interaction: Box<Interaction>,
     },
     #[error("Mismatch {begin} at {begin_position} for {end} at {end_position}")]
     MismatchedBeginEnd {
         begin_position: usize,
-        begin: Interaction,
+        begin: Box<Interaction>,
         end_position: usize,
-        end: Interaction,
+        end: Box<Interaction>,
     },
     #[error("Missing End for {begin} at {position}")]
-    MissingEnd { position: usize, begin: Interaction },
+    MissingEnd {
+        position: usize,
+        begin: Box<Interaction>,
+    },
+    /// The interaction played back is not the one in the pattern.
+    #[error("Received interaction {received}, but expected {expected}")]
+    UnexpectedInteraction {
+        position: usize,
+        expected: Box<Interaction>,
+        received: Box<Interaction>,
+    },
+    /// The interaction played back is past the end of the pattern.
+    #[error("Received interaction, but no more expected interactions: {received}")]
+    Exhausted { received: Box<Interaction> },
+    /// The playback is finalized before the end of the pattern.
+    #[error("Transcript not finished, expecting {expected} at {position}")]
+    Unfinished {
+        position: usize,
+        expected: Box<Interaction>,
+    },
+    /// The transcript is used after it has been finalized, aborted, or has failed.
+    #[error("Transcript is already finalized.")]
+    Finalized,
+    /// An interaction of a pattern played back by a transcript has no type identifier.
+    #[error("Untyped interaction {interaction} at {position}")]
+    Untyped {
+        position: usize,
+        interaction: Box<Interaction>,
+    },
 }
 
 impl InteractionPattern {
@@ -48,6 +94,22 @@ impl InteractionPattern {
         &self.interactions
     }
 
+    /// Check that all interactions have a type identifier.
+    ///
+    /// Patterns parsed from their text format have none, and cannot check the types of the values:
+    /// [`PatternProver`](super::PatternProver) and [`PatternVerifier`](super::PatternVerifier) reject them.
+    pub fn check_typed(&self) -> Result<(), TranscriptError> {
+        self.interactions
+            .iter()
+            .position(|interaction| interaction.type_id().is_none())
+            .map_or(Ok(()), |position| {
+                Err(TranscriptError::Untyped {
+                    position,
+                    interaction: Box::new(self.interactions[position].clone()),
+                })
+            })
+    }
+
     /// Generate a unique identifier for the protocol.
     ///
     /// It is created by taking the SHA3 hash of a stable unambiguous
@@ -63,6 +125,66 @@ impl InteractionPattern {
         result.into()
     }
 
+    /// Render the pattern as a Graphviz DOT graph.
+    ///
+    /// Atomic interactions are drawn with their label, length and type,
+    /// while [`Hierarchy::Begin`] and [`Hierarchy::End`] delimit clusters.
+    ///
+    /// ```
+    /// # use spongefish::pattern::*;
+    /// let mut pattern = PatternState::<u8>::new();
+    /// pattern.begin_protocol::<()>("round");
+    /// pattern.interact(Interaction::new::<u8>(Hierarchy::Atomic, Kind::Message, "commitment", Length::Fixed(32)));
+    /// pattern.end_protocol::<()>("round");
+    /// let dot = pattern.finalize().to_dot();
+    /// assert!(dot.contains("subgraph cluster0 {\n        label=\"round\";"));
+    /// assert!(dot.contains("step0 [label=\"P → V: commitment (32 × u8)\"];"));
+    /// ```
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        self.diagram().to_dot()
+    }
+
+    /// Render the pattern as a Mermaid sequence diagram between the prover and the verifier.
+    ///
+    /// See [`InteractionPattern::to_dot`].
+    #[must_use]
+    pub fn to_mermaid(&self) -> String {
+        self.diagram().to_mermaid()
+    }
+
+    fn diagram(&self) -> Diagram<'_> {
+        let steps = self
+            .interactions
+            .iter()
+            .map(|interaction| match interaction.hierarchy() {
+                Hierarchy::Begin => Step::Begin(interaction.label()),
+                Hierarchy::End => Step::End,
+                Hierarchy::Atomic => Step::Interaction {
+                    kind: interaction.kind(),
+                    label: interaction.label(),
+                    size: Self::size(interaction),
+                },
+            })
+            .collect();
+        Diagram { title: None, steps }
+    }
+
+    /// The size of the values of an interaction, e.g. `32 × u8`.
+    fn size(interaction: &Interaction) -> String {
+        let count = match interaction.length() {
+            Length::None => return String::new(),
+            Length::Scalar => None,
+            Length::Fixed(count) | Length::Runtime(count) => Some(count.to_string()),
+            Length::Dynamic => Some("variable".to_string()),
+        };
+        match (count, interaction.type_id()) {
+            (Some(count), Some(type_id)) => format!("{count} × {type_id}"),
+            (Some(count), None) => count,
+            (None, type_id) => type_id.unwrap_or_default().to_string(),
+        }
+    }
+
     /// Validate the transcript.
     ///
     /// A valid transcript has:
@@ -76,18 +198,18 @@ impl InteractionPattern {
             match interaction.hierarchy() {
                 Hierarchy::Begin => stack.push((position, interaction)),
                 Hierarchy::End => {
-                    let Some((position, begin)) = stack.pop() else {
+                    let Some((begin_position, begin)) = stack.pop() else {
                         return Err(TranscriptError::MissingBegin {
                             position,
-                            end: interaction.clone(),
+                            end: Box::new(interaction.clone()),
                         });
                     };
                     if !interaction.closes(begin) {
                         return Err(TranscriptError::MismatchedBeginEnd {
-                            begin_position: position,
-                            begin: begin.clone(),
-                            end_position: self.interactions.len(),
-                            end: interaction.clone(),
+                            begin_position,
+                            begin: Box::new(begin.clone()),
+                            end_position: position,
+                            end: Box::new(interaction.clone()),
                         });
                     }
                 }
@@ -98,9 +220,9 @@ impl InteractionPattern {
                     if begin.kind() != Kind::Protocol && begin.kind() != interaction.kind() {
                         return Err(TranscriptError::InvalidKind {
                             begin_position,
-                            begin: begin.clone(),
+                            begin: Box::new(begin.clone()),
                             interaction_position: position,
-                            interaction: interaction.clone(),
+                            interaction: Box::new(interaction.clone()),
                         });
                     }
                 }
@@ -109,18 +231,34 @@ impl InteractionPattern {
         if let Some((position, begin)) = stack.pop() {
             return Err(TranscriptError::MissingEnd {
                 position,
-                begin: begin.clone(),
+                begin: Box::new(begin.clone()),
             });
         }
         Ok(())
     }
 }
 
+impl TryFrom<Vec<Interaction>> for InteractionPattern {
+    type Error = TranscriptError;
+
+    fn try_from(interactions: Vec<Interaction>) -> Result<Self, Self::Error> {
+        Self::new(interactions)
+    }
+}
+
+impl From<InteractionPattern> for Vec<Interaction> {
+    fn from(pattern: InteractionPattern) -> Self {
+        pattern.interactions
+    }
+}
+
 /// Creates a human readable representation of the transcript.
 ///
+/// The alternate mode is parsed back with [`FromStr`](core::str::FromStr).
+///
 /// When called in alternate mode `{:#}` it will be a stable format suitable as domain separator.
 impl Display for InteractionPattern {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         // Write the total interactions up front so no prefix string can be a valid domain separator.
         let length = self.interactions.len();
         let width = length.saturating_sub(1).to_string().len();
@@ -152,17 +290,48 @@ mod tests {
     use super::*;
     use crate::pattern::Length;
 
+    #[test]
+    fn test_mermaid() {
+        let transcript = InteractionPattern::new(vec![
+            Interaction::new::<()>(Hierarchy::Begin, Kind::Protocol, "round-0", Length::None),
+            Interaction::new::<u8>(Hierarchy::Atomic, Kind::Public, "statement", Length::Scalar),
+            Interaction::new::<u64>(
+                Hierarchy::Atomic,
+                Kind::Message,
+                "commitment",
+                Length::Runtime(3),
+            ),
+            Interaction::new::<u8>(Hierarchy::Atomic, Kind::Hint, "path", Length::Dynamic),
+            Interaction::new::<()>(Hierarchy::Atomic, Kind::Challenge, "coin", Length::None),
+            Interaction::new::<()>(Hierarchy::End, Kind::Protocol, "round-0", Length::None),
+        ])
+        .unwrap();
+
+        let expected = r"sequenceDiagram
+    participant P as Prover
+    participant V as Verifier
+    rect rgba(128, 128, 128, 0.1)
+    Note over P,V: round-0
+        Note over P,V: public statement (u8)
+        P->>V: commitment (3 × u64)
+        P-->>V: hint path (variable × u8)
+        V->>P: coin
+    end
+";
+        assert_eq!(transcript.to_mermaid(), expected);
+    }
+
     #[test]
     fn test_pattern_hash() {
         let transcript = InteractionPattern::new(vec![
-            Interaction::new::<usize>(Hierarchy::Begin, Kind::Protocol, "test", Length::None),
-            Interaction::new::<Vec<f64>>(
+            Interaction::new::<()>(Hierarchy::Begin, Kind::Protocol, "test", Length::None),
+            Interaction::new::<[u64]>(
                 Hierarchy::Atomic,
                 Kind::Message,
                 "test-message",
                 Length::Scalar,
             ),
-            Interaction::new::<usize>(Hierarchy::End, Kind::Protocol, "test", Length::None),
+            Interaction::new::<()>(Hierarchy::End, Kind::Protocol, "test", Length::None),
         ])
         .unwrap();
 
@@ -2,130 +2,275 @@
 
 mod interaction;
 mod interaction_pattern;
+mod parse;
 mod pattern_player;
 mod pattern_state;
+mod transcript;
+mod transcript_type;
 
 pub use self::{
     interaction::{Hierarchy, Interaction, Kind, Label, Length},
     interaction_pattern::{InteractionPattern, TranscriptError},
+    parse::ParsePatternError,
     pattern_player::PatternPlayer,
     pattern_state::PatternState,
+    transcript::{PatternProver, PatternTranscript, PatternVerifier},
+    transcript_type::TranscriptType,
 };
 
 /// Trait for objects that implement hierarchy operations.
 ///
 /// It does not offer any [`Kind::Atomic`] operations, these need to be implemented specifically.
+///
+/// The `try_` methods return a [`TranscriptError`] if the interaction violates
+/// interaction pattern consistency rules, while the others panic.
 pub trait Pattern {
     /// End a transcript without finalizing it.
     ///
-    /// # Panics
-    ///
-    /// Panics only if the interaction is already finalized or aborted.
+    /// Aborting is possible at any time, including after a failed interaction or another abort.
     fn abort(&mut self);
 
+    /// Begin of a group of interactions.
+    fn try_begin<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        kind: Kind,
+        length: Length,
+    ) -> Result<(), TranscriptError>;
+
+    /// End of a group of interactions.
+    fn try_end<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        kind: Kind,
+        length: Length,
+    ) -> Result<(), TranscriptError>;
+
     /// Begin of a group of interactions.
     ///
     /// # Panics
     ///
-    /// Panics if the interaction violates interaction pattern consistency rules.
-    fn begin<T: ?Sized>(&mut self, label: Label, kind: Kind, length: Length);
+    /// Panics if [`Self::try_begin`] fails.
+    fn begin<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        kind: Kind,
+        length: Length,
+    ) {
+        if let Err(e) = self.try_begin::<T>(label, kind, length) {
+            panic!("{e}");
+        }
+    }
 
     /// End of a group of interactions.
     ///
     /// # Panics
     ///
-    /// Panics if the interaction violates interaction pattern consistency rules.
-    fn end<T: ?Sized>(&mut self, label: Label, kind: Kind, length: Length);
+    /// Panics if [`Self::try_end`] fails.
+    fn end<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        kind: Kind,
+        length: Length,
+    ) {
+        if let Err(e) = self.try_end::<T>(label, kind, length) {
+            panic!("{e}");
+        }
+    }
 
     /// Begin of a subprotocol.
     ///
     /// # Panics
     ///
     /// Panics if the interaction violates interaction pattern consistency rules.
-    fn begin_protocol<T: ?Sized>(&mut self, label: Label) {
+    fn begin_protocol<T: TranscriptType + ?Sized>(&mut self, label: impl Into<Label>) {
         self.begin::<T>(label, Kind::Protocol, Length::None);
     }
 
+    /// Begin of a subprotocol.
+    fn try_begin_protocol<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+    ) -> Result<(), TranscriptError> {
+        self.try_begin::<T>(label, Kind::Protocol, Length::None)
+    }
+
     /// End of a subprotocol.
     ///
     /// # Panics
     ///
     /// Panics if the interaction violates interaction pattern consistency rules.
-    fn end_protocol<T: ?Sized>(&mut self, label: Label) {
+    fn end_protocol<T: TranscriptType + ?Sized>(&mut self, label: impl Into<Label>) {
         self.end::<T>(label, Kind::Protocol, Length::None);
     }
 
+    /// End of a subprotocol.
+    fn try_end_protocol<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+    ) -> Result<(), TranscriptError> {
+        self.try_end::<T>(label, Kind::Protocol, Length::None)
+    }
+
     /// Begin of a public message interaction.
     ///
     /// # Panics
     ///
     /// Panics if the interaction violates interaction pattern consistency rules.
-    fn begin_public<T: ?Sized>(&mut self, label: Label, length: Length) {
+    fn begin_public<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        length: Length,
+    ) {
         self.begin::<T>(label, Kind::Public, length);
     }
 
+    /// Begin of a public message interaction.
+    fn try_begin_public<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.try_begin::<T>(label, Kind::Public, length)
+    }
+
     /// End of a public message interaction.
     ///
     /// # Panics
     ///
     /// Panics if the interaction violates interaction pattern consistency rules.
-    fn end_public<T: ?Sized>(&mut self, label: Label, length: Length) {
+    fn end_public<T: TranscriptType + ?Sized>(&mut self, label: impl Into<Label>, length: Length) {
         self.end::<T>(label, Kind::Public, length);
     }
 
+    /// End of a public message interaction.
+    fn try_end_public<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.try_end::<T>(label, Kind::Public, length)
+    }
+
     /// Begin of a message interaction.
     ///
     /// # Panics
     ///
     /// Panics if the interaction violates interaction pattern consistency rules.
-    fn begin_message<T: ?Sized>(&mut self, label: Label, length: Length) {
+    fn begin_message<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        length: Length,
+    ) {
         self.begin::<T>(label, Kind::Message, length);
     }
 
+    /// Begin of a message interaction.
+    fn try_begin_message<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.try_begin::<T>(label, Kind::Message, length)
+    }
+
     /// End of a message interaction.
     ///
     /// # Panics
     ///
     /// Panics if the interaction violates interaction pattern consistency rules.
-    fn end_message<T: ?Sized>(&mut self, label: Label, length: Length) {
+    fn end_message<T: TranscriptType + ?Sized>(&mut self, label: impl Into<Label>, length: Length) {
         self.end::<T>(label, Kind::Message, length);
     }
 
+    /// End of a message interaction.
+    fn try_end_message<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.try_end::<T>(label, Kind::Message, length)
+    }
+
     /// Begin of a hint interaction.
     ///
     /// # Panics
     ///
     /// Panics if the interaction violates interaction pattern consistency rules.
-    fn begin_hint<T: ?Sized>(&mut self, label: Label, length: Length) {
+    fn begin_hint<T: TranscriptType + ?Sized>(&mut self, label: impl Into<Label>, length: Length) {
         self.begin::<T>(label, Kind::Hint, length);
     }
 
-    /// End of a hint interaction..
+    /// Begin of a hint interaction.
+    fn try_begin_hint<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.try_begin::<T>(label, Kind::Hint, length)
+    }
+
+    /// End of a hint interaction.
     ///
     /// # Panics
     ///
     /// Panics if the interaction violates interaction pattern consistency rules.
-    fn end_hint<T: ?Sized>(&mut self, label: Label, length: Length) {
+    fn end_hint<T: TranscriptType + ?Sized>(&mut self, label: impl Into<Label>, length: Length) {
         self.end::<T>(label, Kind::Hint, length);
     }
 
-    /// Begin of a challenge interaction..
+    /// End of a hint interaction.
+    fn try_end_hint<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.try_end::<T>(label, Kind::Hint, length)
+    }
+
+    /// Begin of a challenge interaction.
     ///
     /// # Panics
     ///
     /// Panics if the interaction violates interaction pattern consistency rules.
-    fn begin_challenge<T: ?Sized>(&mut self, label: Label, length: Length) {
+    fn begin_challenge<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        length: Length,
+    ) {
         self.begin::<T>(label, Kind::Challenge, length);
     }
 
-    /// End of a challenge interaction..
+    /// Begin of a challenge interaction.
+    fn try_begin_challenge<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.try_begin::<T>(label, Kind::Challenge, length)
+    }
+
+    /// End of a challenge interaction.
     ///
     /// # Panics
     ///
     /// Panics if the interaction violates interaction pattern consistency rules.
-    fn end_challenge<T: ?Sized>(&mut self, label: Label, length: Length) {
+    fn end_challenge<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        length: Length,
+    ) {
         self.end::<T>(label, Kind::Challenge, length);
     }
+
+    /// End of a challenge interaction.
+    fn try_end_challenge<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.try_end::<T>(label, Kind::Challenge, length)
+    }
 }
 
 /// Aliases offered for convenience.
@@ -137,6 +282,8 @@ pub use Pattern as Prover;
 
 #[cfg(test)]
 mod tests {
+    use alloc::{boxed::Box, sync::Arc};
+
     use super::*;
 
     #[test]
@@ -167,29 +314,99 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Dropped unfinalized transcript.")]
-    fn panics_if_playback_not_finalized() {
+    fn test_playback_errors() {
+        let nonce =
+            || Interaction::new::<u64>(Hierarchy::Atomic, Kind::Challenge, "nonce", Length::Scalar);
         let mut pattern = PatternState::<u8>::new();
-        pattern.interact(Interaction::new::<u64>(
-            Hierarchy::Atomic,
-            Kind::Challenge,
-            "nonce",
-            Length::Scalar,
-        ));
-        let pattern = pattern.finalize();
+        pattern.try_interact(nonce()).unwrap();
+        pattern.try_interact(nonce()).unwrap();
+        let pattern = Arc::new(pattern.try_finalize().unwrap());
 
-        let mut playback = PatternPlayer::new(pattern.into());
-        playback.interact(Interaction::new::<u64>(
-            Hierarchy::Atomic,
-            Kind::Challenge,
-            "nonce",
-            Length::Scalar,
-        ));
+        // Dropping an unfinished playback does not panic.
+        let mut playback = PatternPlayer::new(pattern.clone());
+        playback.try_interact(nonce()).unwrap();
+        drop(playback);
+
+        let mut playback = PatternPlayer::new(pattern.clone());
+        playback.try_interact(nonce()).unwrap();
+        assert_eq!(
+            playback.try_finalize(),
+            Err(TranscriptError::Unfinished {
+                position: 1,
+                expected: Box::new(nonce()),
+            })
+        );
+
+        let mut playback = PatternPlayer::new(pattern.clone());
+        let challenge =
+            Interaction::new::<u8>(Hierarchy::Atomic, Kind::Challenge, "nonce", Length::Scalar);
+        assert_eq!(
+            playback.try_interact(challenge.clone()),
+            Err(TranscriptError::UnexpectedInteraction {
+                position: 0,
+                expected: Box::new(nonce()),
+                received: Box::new(challenge),
+            })
+        );
+        // A failed playback stays failed, and can be aborted.
+        assert_eq!(
+            playback.try_interact(nonce()),
+            Err(TranscriptError::Finalized)
+        );
+        assert_eq!(
+            playback.try_begin_protocol::<()>("sub"),
+            Err(TranscriptError::Finalized)
+        );
+        playback.abort();
+        assert_eq!(playback.try_finalize(), Err(TranscriptError::Finalized));
+
+        let mut playback = PatternPlayer::new(pattern);
+        playback.try_interact(nonce()).unwrap();
+        playback.try_interact(nonce()).unwrap();
+        assert_eq!(
+            playback.try_interact(nonce()),
+            Err(TranscriptError::Exhausted {
+                received: Box::new(nonce()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_record_errors() {
+        let mut pattern = PatternState::<u8>::new();
+        let end = Interaction::new::<()>(Hierarchy::End, Kind::Protocol, "p", Length::None);
+        assert_eq!(
+            pattern.try_interact(end.clone()),
+            Err(TranscriptError::MissingBegin {
+                position: 0,
+                end: Box::new(end),
+            })
+        );
+        let begin = Interaction::new::<()>(Hierarchy::Begin, Kind::Message, "m", Length::None);
+        pattern.try_interact(begin.clone()).unwrap();
+        let challenge =
+            Interaction::new::<u8>(Hierarchy::Atomic, Kind::Challenge, "c", Length::Scalar);
+        assert_eq!(
+            pattern.try_interact(challenge.clone()),
+            Err(TranscriptError::InvalidKind {
+                begin_position: 0,
+                begin: Box::new(begin.clone()),
+                interaction_position: 1,
+                interaction: Box::new(challenge),
+            })
+        );
+        assert_eq!(
+            pattern.try_finalize(),
+            Err(TranscriptError::MissingEnd {
+                position: 0,
+                begin: Box::new(begin),
+            })
+        );
     }
 
     #[test]
     #[should_panic(
-        expected = "Mismatched begin and end: Begin Protocol Example protocol None (), End Protocol Invalid example protocol None ()"
+        expected = "Mismatch Begin Protocol Example protocol None () at 0 for End Protocol Invalid example protocol None () at 2"
     )]
     fn panics_if_record_begin_end_mismatch() {
         let mut pattern = PatternState::<u8>::new();
@@ -221,7 +438,7 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "Received interaction Atomic Challenge nonce Scalar f64, but expected Atomic Challenge nonce Scalar u64"
+        expected = "Received interaction Atomic Challenge nonce Scalar u32, but expected Atomic Challenge nonce Scalar u64"
     )]
     fn panics_if_type_mismatch() {
         let mut pattern = PatternState::<u8>::new();
@@ -234,7 +451,7 @@ mod tests {
         let pattern = pattern.finalize();
 
         let mut playback = PatternPlayer::new(pattern.into());
-        playback.interact(Interaction::new::<f64>(
+        playback.interact(Interaction::new::<u32>(
             Hierarchy::Atomic,
             Kind::Challenge,
             "nonce",
@@ -245,7 +462,7 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "Received interaction Atomic Public nonce Scalar f64, but expected Atomic Message nonce Scalar u64"
+        expected = "Received interaction Atomic Public nonce Scalar u32, but expected Atomic Message nonce Scalar u64"
     )]
     fn panics_if_kind_mismatch() {
         let mut pattern = PatternState::<u8>::new();
@@ -258,7 +475,7 @@ mod tests {
         let pattern = pattern.finalize();
 
         let mut playback = PatternPlayer::new(pattern.into());
-        playback.interact(Interaction::new::<f64>(
+        playback.interact(Interaction::new::<u32>(
             Hierarchy::Atomic,
             Kind::Public,
             "nonce",
@@ -269,7 +486,7 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "Received interaction Atomic Challenge invalid Scalar f64, but expected Atomic Challenge nonce Scalar u64"
+        expected = "Received interaction Atomic Challenge invalid Scalar u32, but expected Atomic Challenge nonce Scalar u64"
     )]
     fn panics_if_label_mismatch() {
         let mut pattern = PatternState::<u8>::new();
@@ -282,7 +499,7 @@ mod tests {
         let pattern = pattern.finalize();
 
         let mut playback = PatternPlayer::new(pattern.into());
-        playback.interact(Interaction::new::<f64>(
+        playback.interact(Interaction::new::<u32>(
             Hierarchy::Atomic,
             Kind::Challenge,
             "invalid",
@@ -293,7 +510,7 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "Received interaction Atomic Challenge nonce Fixed(1) f64, but expected Atomic Challenge nonce Scalar u64"
+        expected = "Received interaction Atomic Challenge nonce Fixed(1) u32, but expected Atomic Challenge nonce Scalar u64"
     )]
     fn panics_if_length_mismatch() {
         let mut pattern = PatternState::<u8>::new();
@@ -306,7 +523,7 @@ mod tests {
         let pattern = pattern.finalize();
 
         let mut playback = PatternPlayer::new(pattern.into());
-        playback.interact(Interaction::new::<f64>(
+        playback.interact(Interaction::new::<u32>(
             Hierarchy::Atomic,
             Kind::Challenge,
             "nonce",
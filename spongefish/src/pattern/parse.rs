@@ -0,0 +1,349 @@
+use alloc::{format, string::ToString, vec::Vec};
+use core::str::FromStr;
+
+use thiserror::Error;
+
+use super::{Hierarchy, Interaction, InteractionPattern, Kind, Length, TranscriptError};
+
+/// Errors when parsing an interaction pattern from its text format.
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum ParsePatternError {
+    #[error("Expected {expected} at byte {offset}")]
+    Syntax {
+        offset: usize,
+        expected: &'static str,
+    },
+    #[error("Invalid interaction pattern: {0}")]
+    Invalid(#[from] TranscriptError),
+    #[error("Interaction pattern is not in canonical form")]
+    NonCanonical,
+}
+
+/// Parse the stable format of an [`InteractionPattern`], i.e. its alternate [`Display`](core::fmt::Display) `{:#}`.
+///
+/// Only the canonical text is accepted, so that the parsed pattern has the same
+/// [`InteractionPattern::pattern_hash`] as the text it was parsed from.
+/// Type identifiers are not part of this format, so the parsed pattern cannot drive
+/// a [`PatternProver`](super::PatternProver) or a [`PatternVerifier`](super::PatternVerifier),
+/// which check the types of the values: compare its hash to the one of a recorded pattern instead.
+///
+/// ```
+/// # use spongefish::pattern::*;
+/// let mut pattern = PatternState::<u8>::new();
+/// pattern.interact(Interaction::new::<u8>(Hierarchy::Atomic, Kind::Message, "commitment", Length::Fixed(32)));
+/// let pattern = pattern.finalize();
+///
+/// let text = format!("{pattern:#}");
+/// let parsed = text.parse::<InteractionPattern>().unwrap();
+/// assert_eq!(parsed.pattern_hash(), pattern.pattern_hash());
+/// ```
+impl FromStr for InteractionPattern {
+    type Err = ParsePatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            input: s,
+            offset: 0,
+        };
+        parser.expect("Spongefish Transcript (")?;
+        let length = parser.number()?;
+        parser.expect(" interactions)\n")?;
+        let mut interactions = Vec::new();
+        for position in 0..length {
+            if parser.number()? != position {
+                return Err(parser.error("the interaction position"));
+            }
+            parser.expect(" ")?;
+            parser.skip_spaces();
+            let hierarchy = parser.word()?;
+            let kind = parser.word()?;
+            let label_length = parser.number()?;
+            parser.expect(" ")?;
+            let label = parser.take(label_length)?;
+            parser.expect(" ")?;
+            let length = parser.line()?;
+            interactions.push(Interaction::untyped(
+                hierarchy,
+                kind,
+                label.to_string().into(),
+                length,
+            ));
+        }
+        if parser.offset != s.len() {
+            return Err(parser.error("the end of the pattern"));
+        }
+        let pattern = Self::new(interactions)?;
+        // Indentation and zero-padding are fixed by the format.
+        if format!("{pattern:#}") != s {
+            return Err(ParsePatternError::NonCanonical);
+        }
+        Ok(pattern)
+    }
+}
+
+impl FromStr for Hierarchy {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Atomic" => Ok(Self::Atomic),
+            "Begin" => Ok(Self::Begin),
+            "End" => Ok(Self::End),
+            _ => Err(()),
+        }
+    }
+}
+
+impl FromStr for Kind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Protocol" => Ok(Self::Protocol),
+            "Public" => Ok(Self::Public),
+            "Message" => Ok(Self::Message),
+            "Hint" => Ok(Self::Hint),
+            "Challenge" => Ok(Self::Challenge),
+            _ => Err(()),
+        }
+    }
+}
+
+impl FromStr for Length {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "None" => Ok(Self::None),
+            "Scalar" => Ok(Self::Scalar),
+            "Dynamic" => Ok(Self::Dynamic),
+            _ => s
+                .strip_prefix("Fixed(")
+                .and_then(|s| s.strip_suffix(')'))
+                .and_then(parse_decimal)
+                .map(Self::Fixed)
+                .or_else(|| {
+                    s.strip_prefix("Runtime(")
+                        .and_then(|s| s.strip_suffix(')'))
+                        .and_then(parse_decimal)
+                        .map(Self::Runtime)
+                })
+                .ok_or(()),
+        }
+    }
+}
+
+/// Parse a decimal number without sign or leading `+`.
+fn parse_decimal(s: &str) -> Option<usize> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// A cursor over the text of a pattern.
+struct Parser<'a> {
+    input: &'a str,
+    offset: usize,
+}
+
+impl<'a> Parser<'a> {
+    const fn error(&self, expected: &'static str) -> ParsePatternError {
+        ParsePatternError::Syntax {
+            offset: self.offset,
+            expected,
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.offset..]
+    }
+
+    fn expect(&mut self, token: &'static str) -> Result<(), ParsePatternError> {
+        if !self.rest().starts_with(token) {
+            return Err(self.error(token));
+        }
+        self.offset += token.len();
+        Ok(())
+    }
+
+    fn skip_spaces(&mut self) {
+        let rest = self.rest();
+        self.offset += rest.len() - rest.trim_start_matches(' ').len();
+    }
+
+    fn number(&mut self) -> Result<usize, ParsePatternError> {
+        let rest = self.rest();
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let number = parse_decimal(&rest[..digits]).ok_or_else(|| self.error("a number"))?;
+        self.offset += digits;
+        Ok(number)
+    }
+
+    /// Parse a space-terminated token.
+    fn word<T: FromStr>(&mut self) -> Result<T, ParsePatternError> {
+        let rest = self.rest();
+        let word = rest.split(' ').next().unwrap_or_default();
+        let value = word
+            .parse()
+            .map_err(|_| self.error("a hierarchy or kind"))?;
+        self.offset += word.len() + 1;
+        if self.offset > self.input.len() {
+            return Err(self.error("a space"));
+        }
+        Ok(value)
+    }
+
+    /// Parse a newline-terminated length.
+    fn line(&mut self) -> Result<Length, ParsePatternError> {
+        let rest = self.rest();
+        let Some(end) = rest.find('\n') else {
+            return Err(self.error("a newline"));
+        };
+        let length = rest[..end].parse().map_err(|()| self.error("a length"))?;
+        self.offset += end + 1;
+        Ok(length)
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a str, ParsePatternError> {
+        let rest = self.rest();
+        let taken = rest.get(..length).ok_or_else(|| self.error("a label"))?;
+        self.offset += length;
+        Ok(taken)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::{Pattern, PatternState};
+
+    fn example_pattern() -> InteractionPattern {
+        let mut pattern = PatternState::<u8>::new();
+        pattern.begin_protocol::<()>("Example protocol");
+        for label in ["", "with spaces", "with\nnewline", "ünïcödé"] {
+            pattern.interact(Interaction::new::<u8>(
+                Hierarchy::Atomic,
+                Kind::Message,
+                label,
+                Length::Fixed(12),
+            ));
+        }
+        pattern.begin_challenge::<[u8]>("challenges", Length::Dynamic);
+        pattern.interact(Interaction::new::<u64>(
+            Hierarchy::Atomic,
+            Kind::Challenge,
+            "nonce",
+            Length::Scalar,
+        ));
+        pattern.end_challenge::<[u8]>("challenges", Length::Dynamic);
+        pattern.interact(Interaction::new::<()>(
+            Hierarchy::Atomic,
+            Kind::Hint,
+            "hint",
+            Length::None,
+        ));
+        pattern.end_protocol::<()>("Example protocol");
+        pattern.finalize()
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let pattern = example_pattern();
+        let text = format!("{pattern:#}");
+        let parsed = text.parse::<InteractionPattern>().unwrap();
+        assert_eq!(format!("{parsed:#}"), text);
+        assert_eq!(parsed.pattern_hash(), pattern.pattern_hash());
+        assert_eq!(parsed.interactions().len(), pattern.interactions().len());
+        for (parsed, recorded) in parsed.interactions().iter().zip(pattern.interactions()) {
+            assert_eq!(parsed.type_id(), None);
+            assert!(parsed.matches(recorded));
+        }
+    }
+
+    #[test]
+    fn test_parsed_pattern_plays_back() {
+        let pattern = format!("{:#}", example_pattern())
+            .parse::<InteractionPattern>()
+            .unwrap();
+        let mut player = crate::pattern::PatternPlayer::new(pattern.into());
+        player.begin_protocol::<()>("Example protocol");
+        for label in ["", "with spaces", "with\nnewline", "ünïcödé"] {
+            player.interact(Interaction::new::<u8>(
+                Hierarchy::Atomic,
+                Kind::Message,
+                label,
+                Length::Fixed(12),
+            ));
+        }
+        player.abort();
+    }
+
+    #[test]
+    fn test_parse_length() {
+        for length in [
+            Length::None,
+            Length::Scalar,
+            Length::Fixed(12),
+            Length::Dynamic,
+            Length::Runtime(0),
+        ] {
+            assert_eq!(length.to_string().parse(), Ok(length));
+        }
+        assert_eq!("Runtime(-1)".parse::<Length>(), Err(()));
+        assert_eq!("Runtime()".parse::<Length>(), Err(()));
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        let text = format!("{:#}", example_pattern());
+        let cases = [
+            String::new(),
+            text.replace("Spongefish", "spongefish"),
+            text.replace("(10 interactions)", "(11 interactions)"),
+            text.replace("(10 interactions)", "(9 interactions)"),
+            text.replace("Fixed(12)", "Fixed(+12)"),
+            text.replace("Message", "Messages"),
+            text.replace("11 with spaces", "12 with spaces"),
+            text.replace("5 nonce", "50 nonce"),
+            text[..text.len() - 1].to_string(),
+        ];
+        for case in cases {
+            assert!(case.parse::<InteractionPattern>().is_err(), "{case}");
+        }
+
+        // Indentation is part of the format.
+        let unindented = text.replace("\n1   Atomic", "\n1 Atomic");
+        assert_eq!(
+            unindented.parse::<InteractionPattern>(),
+            Err(ParsePatternError::NonCanonical)
+        );
+
+        // Parsed patterns are validated.
+        let unbalanced = "Spongefish Transcript (1 interactions)\n0 End Protocol 1 a None\n";
+        assert!(matches!(
+            unbalanced.parse::<InteractionPattern>(),
+            Err(ParsePatternError::Invalid(
+                TranscriptError::MissingBegin { .. }
+            ))
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_round_trip() {
+        let pattern = example_pattern();
+        let json = serde_json::to_string(&pattern).unwrap();
+        assert!(json.starts_with(
+            r#"[{"hierarchy":"Begin","kind":"Protocol","label":"Example protocol","type_id":"()","length":"None"}"#
+        ));
+        let parsed: InteractionPattern = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, pattern);
+        assert_eq!(parsed.pattern_hash(), pattern.pattern_hash());
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+
+        // Deserialized patterns are validated.
+        let unbalanced = r#"[{"hierarchy":"End","kind":"Protocol","label":"a","length":"None"}]"#;
+        assert!(serde_json::from_str::<InteractionPattern>(unbalanced).is_err());
+    }
+}
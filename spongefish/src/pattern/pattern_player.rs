@@ -1,13 +1,15 @@
-use std::sync::Arc;
+use alloc::{boxed::Box, sync::Arc};
 
-use super::{Interaction, InteractionPattern, Kind, Label, Length};
+use super::{
+    Interaction, InteractionPattern, Kind, Label, Length, TranscriptError, TranscriptType,
+};
 use crate::pattern::Hierarchy;
 
 /// Play back an interaction pattern and make sure all interactions match up.
 ///
-/// # Panics
-///
-/// Panics on [`Drop`] if there are unfinished interactions.
+/// The `try_` methods return a [`TranscriptError`] on mismatches, while the others panic.
+/// Dropping a player before the end of the pattern is not an error,
+/// so that a failing verifier can bail out early: use [`Self::try_finalize`] to check completion.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct PatternPlayer {
     /// Shared reference to the transcript.
@@ -28,59 +30,141 @@ impl PatternPlayer {
         }
     }
 
-    /// Finalize the sequence of interactions. Returns an error if there
-    /// are unfinished interactions.
+    /// Finalize the sequence of interactions.
     ///
     /// # Panics
     ///
-    /// Panics if the transcript is already finalized or if there are expected interactions left.
-    pub fn finalize(mut self) {
-        assert!(self.position <= self.pattern.interactions().len());
-        assert!(!self.finalized, "Transcript is already finalized.");
-        assert!(
-            self.position >= self.pattern.interactions().len(),
-            "Transcript not finished, expecting {}",
-            self.pattern.interactions()[self.position]
-        );
-        self.finalized = true;
+    /// Panics if [`Self::try_finalize`] fails.
+    pub fn finalize(self) {
+        if let Err(e) = self.try_finalize() {
+            panic!("{e}");
+        }
+    }
+
+    /// Finalize the sequence of interactions.
+    ///
+    /// Fails if the transcript is already finalized or if there are expected interactions left.
+    pub fn try_finalize(self) -> Result<(), TranscriptError> {
+        if self.finalized {
+            return Err(TranscriptError::Finalized);
+        }
+        self.pattern
+            .interactions()
+            .get(self.position)
+            .map_or(Ok(()), |expected| {
+                Err(TranscriptError::Unfinished {
+                    position: self.position,
+                    expected: Box::new(expected.clone()),
+                })
+            })
     }
 
     /// Play the next interaction in the pattern.
     ///
     /// # Panics
     ///
-    /// Panics if the transcript is already finalized or if the interaction does not match the expected one.
+    /// Panics if [`Self::try_interact`] fails.
     pub fn interact(&mut self, interaction: Interaction) {
-        assert!(!self.finalized, "Transcript is already finalized.");
+        if let Err(e) = self.try_interact(interaction) {
+            panic!("{e}");
+        }
+    }
+
+    /// Play the next interaction in the pattern.
+    ///
+    /// Fails if the transcript is already finalized or if the interaction does not match the expected one.
+    /// After a failure, the transcript is finalized.
+    pub fn try_interact(&mut self, interaction: Interaction) -> Result<(), TranscriptError> {
+        if self.finalized {
+            return Err(TranscriptError::Finalized);
+        }
         let Some(expected) = self.pattern.interactions().get(self.position) else {
             self.finalized = true;
-            panic!("Received interaction, but no more expected interactions: {interaction}");
+            return Err(TranscriptError::Exhausted {
+                received: Box::new(interaction),
+            });
         };
-        if expected != &interaction {
+        if !expected.matches(&interaction) {
             self.finalized = true;
-            panic!("Received interaction {interaction}, but expected {expected}");
+            return Err(TranscriptError::UnexpectedInteraction {
+                position: self.position,
+                expected: Box::new(expected.clone()),
+                received: Box::new(interaction),
+            });
         }
         self.position += 1;
+        Ok(())
+    }
+
+    /// Play the next interaction in the pattern, whose number of values is not known yet.
+    ///
+    /// The `interaction` is checked as [`Self::try_interact`] does, except for its length,
+    /// and the number of values recorded in the pattern as a [`Length::Runtime`] is returned, if any.
+    /// It is a bound to check before reading the values, and to pass to [`Self::try_check_length`] after.
+    pub fn try_interact_dyn(
+        &mut self,
+        interaction: Interaction,
+    ) -> Result<Option<usize>, TranscriptError> {
+        let recorded = match self.expected().map(Interaction::length) {
+            Some(Length::Runtime(length)) => Some(length),
+            _ => None,
+        };
+        let length = recorded.map_or(Length::Dynamic, Length::Runtime);
+        self.try_interact(interaction.with_length(length))?;
+        Ok(recorded)
+    }
+
+    /// Check the actual number of values of the last interaction played by [`Self::try_interact_dyn`].
+    ///
+    /// Fails if it differs from the one recorded in the pattern.
+    /// After a failure, the transcript is finalized.
+    pub fn try_check_length(&mut self, length: usize) -> Result<(), TranscriptError> {
+        if self.finalized {
+            return Err(TranscriptError::Finalized);
+        }
+        let Some(position) = self.position.checked_sub(1) else {
+            return Ok(());
+        };
+        let expected = &self.pattern.interactions()[position];
+        match expected.length() {
+            Length::Runtime(recorded) if recorded != length => {
+                self.finalized = true;
+                Err(TranscriptError::UnexpectedInteraction {
+                    position,
+                    expected: Box::new(expected.clone()),
+                    received: Box::new(expected.clone().with_length(Length::Runtime(length))),
+                })
+            }
+            _ => Ok(()),
+        }
     }
-}
 
-impl Drop for PatternPlayer {
-    fn drop(&mut self) {
-        assert!(self.finalized, "Dropped unfinalized transcript.");
+    /// The next expected interaction, if any.
+    fn expected(&self) -> Option<&Interaction> {
+        self.pattern.interactions().get(self.position)
     }
 }
 
 impl super::Pattern for PatternPlayer {
     fn abort(&mut self) {
-        assert!(!self.finalized, "Transcript is already finalized.");
         self.finalized = true;
     }
 
-    fn begin<T: ?Sized>(&mut self, label: Label, kind: Kind, length: Length) {
-        self.interact(Interaction::new::<T>(Hierarchy::Begin, kind, label, length));
+    fn try_begin<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        kind: Kind,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.try_interact(Interaction::new::<T>(Hierarchy::Begin, kind, label, length))
     }
 
-    fn end<T: ?Sized>(&mut self, label: Label, kind: Kind, length: Length) {
-        self.interact(Interaction::new::<T>(Hierarchy::End, kind, label, length));
+    fn try_end<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        kind: Kind,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.try_interact(Interaction::new::<T>(Hierarchy::End, kind, label, length))
     }
 }
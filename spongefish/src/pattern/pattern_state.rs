@@ -1,13 +1,16 @@
-use std::marker::PhantomData;
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
 
-use super::{Hierarchy, Interaction, InteractionPattern, Kind, Label, Length};
+use super::{
+    Hierarchy, Interaction, InteractionPattern, Kind, Label, Length, TranscriptError,
+    TranscriptType,
+};
 use crate::Unit;
 
 /// Records an interaction pattern.
 ///
-/// # Panics
-///
-/// Panics on [`Drop`] if there are unfinished interactions.
+/// The `try_` methods return a [`TranscriptError`] on inconsistent interactions,
+/// while the others panic.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
 pub struct PatternState<U = u8>
 where
@@ -33,65 +36,97 @@ where
         }
     }
 
+    /// Finish recording, returning the pattern.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`Self::try_finalize`] fails.
     #[must_use]
     pub fn finalize(self) -> InteractionPattern {
-        assert!(!self.finalized, "Transcript is already finalized.");
-        match InteractionPattern::new(self.interactions) {
-            Ok(transcript) => transcript,
-            Err(e) => panic!("Error validating interaction pattern: {e}"),
+        self.try_finalize()
+            .unwrap_or_else(|e| panic!("Error validating interaction pattern: {e}"))
+    }
+
+    /// Finish recording, returning the pattern.
+    ///
+    /// Fails if the recording was aborted or the pattern is not valid,
+    /// e.g. because a [`Hierarchy::Begin`] is never closed.
+    pub fn try_finalize(self) -> Result<InteractionPattern, TranscriptError> {
+        if self.finalized {
+            return Err(TranscriptError::Finalized);
         }
+        InteractionPattern::new(self.interactions)
     }
 
     /// Add a new interaction to the pattern.
     ///
     /// # Panics
     ///
-    /// Panics if
+    /// Panics if [`Self::try_interact`] fails.
+    pub fn interact(&mut self, interaction: Interaction) {
+        if let Err(e) = self.try_interact(interaction) {
+            panic!("{e}");
+        }
+    }
+
+    /// Add a new interaction to the pattern.
+    ///
+    /// Fails if
     /// - the interaction does not match the parent kind and
     ///   the parent kind is not [`Kind::Protocol`],
-    /// - the it is an [`Hierarchy::End`], but there is either no
-    ///   [`Hierarchy::Begin`] or it does not match the end.being
-    pub fn interact(&mut self, interaction: Interaction) {
-        assert!(!self.finalized, "Transcript is already finalized.");
-        if let Some(begin) = self.last_open_begin() {
+    /// - it is an [`Hierarchy::End`], but there is either no
+    ///   [`Hierarchy::Begin`] or it does not match the end.
+    pub fn try_interact(&mut self, interaction: Interaction) -> Result<(), TranscriptError> {
+        if self.finalized {
+            return Err(TranscriptError::Finalized);
+        }
+        let position = self.interactions.len();
+        if let Some((begin_position, begin)) = self.last_open_begin() {
             // Check if the new interaction is of a permissible kind.
-            assert!(
-                begin.kind() == Kind::Protocol || begin.kind() == interaction.kind(),
-                "Invalid interaction kind: expected {}, got {}",
-                begin.kind(),
-                interaction.kind()
-            );
+            if begin.kind() != Kind::Protocol && begin.kind() != interaction.kind() {
+                return Err(TranscriptError::InvalidKind {
+                    begin_position,
+                    begin: Box::new(begin.clone()),
+                    interaction_position: position,
+                    interaction: Box::new(interaction),
+                });
+            }
             // Check if it is a matching End to the current Begin
-            assert!(
-                interaction.hierarchy() != Hierarchy::End || interaction.closes(begin),
-                "Mismatched begin and end: {begin}, {interaction}"
-            );
-        } else {
-            // No unclosed Begin interaction. Make sure this is not an end.
-            assert!(
-                interaction.hierarchy() != Hierarchy::End,
-                "Missing begin for {interaction}"
-            );
+            if interaction.hierarchy() == Hierarchy::End && !interaction.closes(begin) {
+                return Err(TranscriptError::MismatchedBeginEnd {
+                    begin_position,
+                    begin: Box::new(begin.clone()),
+                    end_position: position,
+                    end: Box::new(interaction),
+                });
+            }
+        } else if interaction.hierarchy() == Hierarchy::End {
+            // No unclosed Begin interaction.
+            return Err(TranscriptError::MissingBegin {
+                position,
+                end: Box::new(interaction),
+            });
         }
 
         // All good, append
         self.interactions.push(interaction);
+        Ok(())
     }
 
-    /// Return the last unclosed [`Hierarchy::Begin`] interaction.
-    fn last_open_begin(&self) -> Option<&Interaction> {
+    /// Return the last unclosed [`Hierarchy::Begin`] interaction, and its position.
+    fn last_open_begin(&self) -> Option<(usize, &Interaction)> {
         // Reverse search to find matching begin
         let mut stack = 0;
-        for interaction in self.interactions.iter().rev() {
+        for (position, interaction) in self.interactions.iter().enumerate().rev() {
             match interaction.hierarchy() {
                 Hierarchy::End => stack += 1,
                 Hierarchy::Begin => {
                     if stack == 0 {
-                        return Some(interaction);
+                        return Some((position, interaction));
                     }
                     stack -= 1;
                 }
-                _ => {}
+                Hierarchy::Atomic => {}
             }
         }
         None
@@ -103,15 +138,24 @@ where
     U: Unit,
 {
     fn abort(&mut self) {
-        assert!(!self.finalized, "Transcript is already finalized.");
         self.finalized = true;
     }
 
-    fn begin<T: ?Sized>(&mut self, label: Label, kind: Kind, length: Length) {
-        self.interact(Interaction::new::<T>(Hierarchy::Begin, kind, label, length));
+    fn try_begin<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        kind: Kind,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.try_interact(Interaction::new::<T>(Hierarchy::Begin, kind, label, length))
     }
 
-    fn end<T: ?Sized>(&mut self, label: Label, kind: Kind, length: Length) {
-        self.interact(Interaction::new::<T>(Hierarchy::End, kind, label, length));
+    fn try_end<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        kind: Kind,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.try_interact(Interaction::new::<T>(Hierarchy::End, kind, label, length))
     }
 }
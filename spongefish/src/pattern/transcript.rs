@@ -0,0 +1,754 @@
+use alloc::{sync::Arc, vec::Vec};
+
+use rand::{CryptoRng, RngCore};
+
+use super::{
+    Hierarchy, Interaction, InteractionPattern, Kind, Label, Length, Pattern, PatternPlayer,
+    TranscriptError, TranscriptType,
+};
+use crate::{
+    DefaultHash, DomainSeparatorMismatch, DuplexSpongeInterface, HashStateWithInstructions,
+    ProofResult, ProverState, Unit, UnitTranscript, VerifierState,
+};
+
+/// A transcript checked against an [`InteractionPattern`], as [`PatternProver`] and [`PatternVerifier`].
+///
+/// Every interaction goes through [`PatternTranscript::interact`],
+/// which checks it against the pattern (including the type of the values)
+/// before performing it on the underlying prover or verifier state.
+/// Slices of values are expected as [`Length::Fixed`], variable-length messages and hints
+/// as [`Length::Runtime`] with their actual length, which a [`Length::Dynamic`] in the pattern accepts.
+///
+/// The codecs provide typed interactions on top of it,
+/// e.g. `FieldPatternProver` and `GroupPatternVerifier`.
+pub trait PatternTranscript {
+    /// The underlying prover or verifier state.
+    type State;
+
+    /// Check the next atomic interaction, on values of type `T`, against the pattern,
+    /// then perform it with `f`.
+    ///
+    /// If the interaction does not match the pattern, `f` is not called
+    /// and the [`TranscriptError`] is returned, as are all later interactions.
+    fn interact<T: TranscriptType<Family> + ?Sized, Family, O, E: From<TranscriptError>>(
+        &mut self,
+        kind: Kind,
+        label: impl Into<Label>,
+        length: Length,
+        f: impl FnOnce(&mut Self::State) -> Result<O, E>,
+    ) -> Result<O, E>;
+
+    /// Check the next atomic interaction, on a variable number of values of type `T`,
+    /// against the pattern, then perform it with `f` and check the number of values returned by `length`.
+    ///
+    /// This is for values whose number is only known once performed, e.g. when read by the verifier.
+    /// Everything but the length is checked before calling `f`, which receives the maximal number
+    /// of values: the one recorded in the pattern as a [`Length::Runtime`], if any, or `usize::MAX`.
+    /// `f` must reject a longer length prefix before reading the values.
+    /// If the interaction does not match the pattern, the [`TranscriptError`] is returned,
+    /// as are all later interactions.
+    fn interact_dyn<T: TranscriptType<Family> + ?Sized, Family, O, E: From<TranscriptError>>(
+        &mut self,
+        kind: Kind,
+        label: impl Into<Label>,
+        f: impl FnOnce(&mut Self::State, usize) -> Result<O, E>,
+        length: impl FnOnce(&O) -> usize,
+    ) -> Result<O, E>;
+}
+
+// As for `ProverState`, the random number generator has a default only with `std`.
+macro_rules! pattern_prover {
+    ($($generics:tt)*) => {
+        /// A prover transcript following an [`InteractionPattern`].
+        ///
+        /// The sponge is initialised with the [`InteractionPattern::pattern_hash`],
+        /// and interactions are checked by a [`PatternPlayer`] instead of a domain separator.
+        /// Begin and end interactions are sent with the [`Pattern`] trait.
+        ///
+        /// ```
+        /// # use spongefish::pattern::*;
+        /// # use std::sync::Arc;
+        ///
+        /// let mut pattern = PatternState::<u8>::new();
+        /// pattern.interact(Interaction::new::<u8>(Hierarchy::Atomic, Kind::Message, "commitment", Length::Fixed(2)));
+        /// pattern.interact(Interaction::new::<u8>(Hierarchy::Atomic, Kind::Challenge, "challenge", Length::Fixed(16)));
+        /// let pattern = Arc::new(pattern.finalize());
+        ///
+        /// let mut prover_state = PatternProver::<spongefish::DefaultHash>::from(pattern.clone());
+        /// prover_state.add_units("commitment", &[1, 2]).unwrap();
+        /// let mut challenge = [0u8; 16];
+        /// prover_state.fill_challenge_units("challenge", &mut challenge).unwrap();
+        /// let narg_string = prover_state.finish().unwrap();
+        ///
+        /// let mut verifier_state = PatternVerifier::<spongefish::DefaultHash>::new(pattern, &narg_string);
+        /// let mut commitment = [0u8; 2];
+        /// verifier_state.fill_next_units("commitment", &mut commitment).unwrap();
+        /// let mut verifier_challenge = [0u8; 16];
+        /// verifier_state.fill_challenge_units("challenge", &mut verifier_challenge).unwrap();
+        /// assert_eq!(verifier_challenge, challenge);
+        /// verifier_state.finish().unwrap();
+        /// ```
+        ///
+        /// # Panics
+        ///
+        /// The panicking [`Pattern`] methods, such as [`Pattern::begin_protocol`], panic if the interaction
+        /// does not match the pattern: the `try_` ones, such as [`Pattern::try_begin_protocol`],
+        /// and the other interactions return an error instead.
+        pub struct PatternProver<$($generics)*>
+        where
+            U: Unit,
+            H: DuplexSpongeInterface<U>,
+            R: RngCore + CryptoRng,
+        {
+            state: ProverState<H, U, R>,
+            player: PatternPlayer,
+        }
+    };
+}
+
+#[cfg(feature = "std")]
+pattern_prover!(H = DefaultHash, U = u8, R = crate::DefaultRng);
+#[cfg(not(feature = "std"))]
+pattern_prover!(H, U, R);
+
+/// A verifier transcript following an [`InteractionPattern`].
+///
+/// See [`PatternProver`].
+pub struct PatternVerifier<'a, H = DefaultHash, U = u8>
+where
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+{
+    state: VerifierState<'a, H, U>,
+    player: PatternPlayer,
+}
+
+impl<H, U, R> PatternProver<H, U, R>
+where
+    U: Unit + TranscriptType,
+    H: DuplexSpongeInterface<U>,
+    R: RngCore + CryptoRng,
+{
+    /// Create a prover state for `pattern`, with private coins seeded by `csrng`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`Self::try_new`] fails.
+    pub fn new(pattern: Arc<InteractionPattern>, csrng: R) -> Self {
+        Self::try_new(pattern, csrng).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Create a prover state for `pattern`, with private coins seeded by `csrng`.
+    ///
+    /// Fails if the pattern has untyped interactions (see [`InteractionPattern::check_typed`]).
+    pub fn try_new(pattern: Arc<InteractionPattern>, csrng: R) -> Result<Self, TranscriptError> {
+        pattern.check_typed()?;
+        let pattern_hash = pattern.pattern_hash();
+        let hash_state = HashStateWithInstructions::unchecked(pattern_hash);
+        Ok(Self {
+            state: ProverState::with_hash_state(hash_state, &pattern_hash, csrng),
+            player: PatternPlayer::new(pattern),
+        })
+    }
+
+    /// Add prover messages to the NARG string.
+    pub fn add_units(
+        &mut self,
+        label: impl Into<Label>,
+        input: &[U],
+    ) -> Result<(), DomainSeparatorMismatch> {
+        self.interact::<U, (), _, _>(Kind::Message, label, Length::Fixed(input.len()), |state| {
+            state.add_units(input)
+        })
+    }
+
+    /// Add a variable-length prover message to the NARG string, prefixed by its length.
+    pub fn add_units_dyn(
+        &mut self,
+        label: impl Into<Label>,
+        input: &[U],
+    ) -> Result<(), DomainSeparatorMismatch>
+    where
+        U: From<u8>,
+    {
+        let length = Length::Runtime(input.len());
+        self.interact::<U, (), _, _>(Kind::Message, label, length, |state| {
+            state.add_units_dyn(input)
+        })
+    }
+
+    /// Absorb public messages, without writing them to the NARG string.
+    pub fn public_units(
+        &mut self,
+        label: impl Into<Label>,
+        input: &[U],
+    ) -> Result<(), DomainSeparatorMismatch> {
+        self.interact::<U, (), _, _>(Kind::Public, label, Length::Fixed(input.len()), |state| {
+            state.public_units(input)
+        })
+    }
+
+    /// Send a hint.
+    pub fn hint_bytes(
+        &mut self,
+        label: impl Into<Label>,
+        hint: &[u8],
+    ) -> Result<(), DomainSeparatorMismatch> {
+        let length = Length::Runtime(hint.len());
+        self.interact::<u8, (), _, _>(Kind::Hint, label, length, |state| state.hint_bytes(hint))
+    }
+
+    /// Squeeze a verifier challenge.
+    pub fn fill_challenge_units(
+        &mut self,
+        label: impl Into<Label>,
+        output: &mut [U],
+    ) -> Result<(), DomainSeparatorMismatch> {
+        self.interact::<U, (), _, _>(
+            Kind::Challenge,
+            label,
+            Length::Fixed(output.len()),
+            |state| state.fill_challenge_units(output),
+        )
+    }
+
+    /// Return the NARG string written so far.
+    #[must_use]
+    pub fn narg_string(&self) -> &[u8] {
+        self.state.narg_string()
+    }
+
+    /// Return the NARG string, checking that the pattern has been followed entirely.
+    pub fn finish(self) -> ProofResult<Vec<u8>> {
+        self.player.try_finalize()?;
+        self.state.finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H, U> From<Arc<InteractionPattern>> for PatternProver<H, U, crate::DefaultRng>
+where
+    U: Unit + TranscriptType,
+    H: DuplexSpongeInterface<U>,
+{
+    fn from(pattern: Arc<InteractionPattern>) -> Self {
+        Self::new(pattern, crate::DefaultRng::default())
+    }
+}
+
+impl<H, U, R> PatternTranscript for PatternProver<H, U, R>
+where
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+    R: RngCore + CryptoRng,
+{
+    type State = ProverState<H, U, R>;
+
+    fn interact<T: TranscriptType<Family> + ?Sized, Family, O, E: From<TranscriptError>>(
+        &mut self,
+        kind: Kind,
+        label: impl Into<Label>,
+        length: Length,
+        f: impl FnOnce(&mut Self::State) -> Result<O, E>,
+    ) -> Result<O, E> {
+        self.player.try_interact(Interaction::with_type_id(
+            Hierarchy::Atomic,
+            kind,
+            label,
+            length,
+            T::type_id(),
+        ))?;
+        f(&mut self.state)
+    }
+    fn interact_dyn<T: TranscriptType<Family> + ?Sized, Family, O, E: From<TranscriptError>>(
+        &mut self,
+        kind: Kind,
+        label: impl Into<Label>,
+        f: impl FnOnce(&mut Self::State, usize) -> Result<O, E>,
+        length: impl FnOnce(&O) -> usize,
+    ) -> Result<O, E> {
+        interact_dyn::<T, _, _, _, _>(&mut self.player, &mut self.state, kind, label, f, length)
+    }
+}
+
+impl<H, U, R> Pattern for PatternProver<H, U, R>
+where
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+    R: RngCore + CryptoRng,
+{
+    fn abort(&mut self) {
+        self.player.abort();
+    }
+
+    fn try_begin<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        kind: Kind,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.player.try_begin::<T>(label, kind, length)
+    }
+
+    fn try_end<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        kind: Kind,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.player.try_end::<T>(label, kind, length)
+    }
+}
+
+impl<'a, H, U> PatternVerifier<'a, H, U>
+where
+    U: Unit + TranscriptType,
+    H: DuplexSpongeInterface<U>,
+{
+    /// Create a verifier state for `pattern`, reading the NARG string `narg_string`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`Self::try_new`] fails.
+    #[must_use]
+    pub fn new(pattern: Arc<InteractionPattern>, narg_string: &'a [u8]) -> Self {
+        Self::try_new(pattern, narg_string).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Create a verifier state for `pattern`, reading the NARG string `narg_string`.
+    ///
+    /// Fails if the pattern has untyped interactions (see [`InteractionPattern::check_typed`]).
+    pub fn try_new(
+        pattern: Arc<InteractionPattern>,
+        narg_string: &'a [u8],
+    ) -> Result<Self, TranscriptError> {
+        pattern.check_typed()?;
+        let hash_state = HashStateWithInstructions::unchecked(pattern.pattern_hash());
+        Ok(Self {
+            state: VerifierState::with_source(hash_state, narg_string),
+            player: PatternPlayer::new(pattern),
+        })
+    }
+
+    /// Read prover messages from the NARG string.
+    pub fn fill_next_units(
+        &mut self,
+        label: impl Into<Label>,
+        output: &mut [U],
+    ) -> Result<(), DomainSeparatorMismatch> {
+        self.interact::<U, (), _, _>(Kind::Message, label, Length::Fixed(output.len()), |state| {
+            state.fill_next_units(output)
+        })
+    }
+
+    /// Read a variable-length prover message from the NARG string.
+    pub fn next_units_vec(
+        &mut self,
+        label: impl Into<Label>,
+    ) -> Result<Vec<U>, DomainSeparatorMismatch>
+    where
+        U: From<u8> + Default,
+    {
+        self.interact_dyn::<U, (), _, _>(
+            Kind::Message,
+            label,
+            VerifierState::next_units_vec_bounded,
+            Vec::len,
+        )
+    }
+
+    /// Absorb public messages.
+    pub fn public_units(
+        &mut self,
+        label: impl Into<Label>,
+        input: &[U],
+    ) -> Result<(), DomainSeparatorMismatch> {
+        self.interact::<U, (), _, _>(Kind::Public, label, Length::Fixed(input.len()), |state| {
+            state.public_units(input)
+        })
+    }
+
+    /// Read a hint from the NARG string.
+    pub fn hint_bytes(
+        &mut self,
+        label: impl Into<Label>,
+    ) -> Result<&'a [u8], DomainSeparatorMismatch> {
+        self.interact_dyn::<u8, (), _, _>(
+            Kind::Hint,
+            label,
+            VerifierState::hint_bytes_bounded,
+            |hint| hint.len(),
+        )
+    }
+
+    /// Squeeze a verifier challenge.
+    pub fn fill_challenge_units(
+        &mut self,
+        label: impl Into<Label>,
+        output: &mut [U],
+    ) -> Result<(), DomainSeparatorMismatch> {
+        self.interact::<U, (), _, _>(
+            Kind::Challenge,
+            label,
+            Length::Fixed(output.len()),
+            |state| state.fill_challenge_units(output),
+        )
+    }
+
+    /// Check that the pattern has been followed and the NARG string read entirely.
+    pub fn finish(self) -> ProofResult<()> {
+        self.player.try_finalize()?;
+        self.state.finish()
+    }
+}
+
+impl<'a, H, U> PatternTranscript for PatternVerifier<'a, H, U>
+where
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+{
+    type State = VerifierState<'a, H, U>;
+
+    fn interact<T: TranscriptType<Family> + ?Sized, Family, O, E: From<TranscriptError>>(
+        &mut self,
+        kind: Kind,
+        label: impl Into<Label>,
+        length: Length,
+        f: impl FnOnce(&mut Self::State) -> Result<O, E>,
+    ) -> Result<O, E> {
+        self.player.try_interact(Interaction::with_type_id(
+            Hierarchy::Atomic,
+            kind,
+            label,
+            length,
+            T::type_id(),
+        ))?;
+        f(&mut self.state)
+    }
+    fn interact_dyn<T: TranscriptType<Family> + ?Sized, Family, O, E: From<TranscriptError>>(
+        &mut self,
+        kind: Kind,
+        label: impl Into<Label>,
+        f: impl FnOnce(&mut Self::State, usize) -> Result<O, E>,
+        length: impl FnOnce(&O) -> usize,
+    ) -> Result<O, E> {
+        interact_dyn::<T, _, _, _, _>(&mut self.player, &mut self.state, kind, label, f, length)
+    }
+}
+
+/// Play a dynamic interaction on `player`, as [`PatternTranscript::interact_dyn`] does on `state`.
+fn interact_dyn<T: TranscriptType<Family> + ?Sized, Family, S, O, E: From<TranscriptError>>(
+    player: &mut PatternPlayer,
+    state: &mut S,
+    kind: Kind,
+    label: impl Into<Label>,
+    f: impl FnOnce(&mut S, usize) -> Result<O, E>,
+    length: impl FnOnce(&O) -> usize,
+) -> Result<O, E> {
+    let max_len = player.try_interact_dyn(Interaction::with_type_id(
+        Hierarchy::Atomic,
+        kind,
+        label,
+        Length::Dynamic,
+        T::type_id(),
+    ))?;
+    let output = f(state, max_len.unwrap_or(usize::MAX))?;
+    player.try_check_length(length(&output))?;
+    Ok(output)
+}
+
+impl<H, U> Pattern for PatternVerifier<'_, H, U>
+where
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+{
+    fn abort(&mut self) {
+        self.player.abort();
+    }
+
+    fn try_begin<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        kind: Kind,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.player.try_begin::<T>(label, kind, length)
+    }
+
+    fn try_end<T: TranscriptType + ?Sized>(
+        &mut self,
+        label: impl Into<Label>,
+        kind: Kind,
+        length: Length,
+    ) -> Result<(), TranscriptError> {
+        self.player.try_end::<T>(label, kind, length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pattern::PatternState, DefaultRng, DomainSeparator};
+
+    fn example_pattern() -> Arc<InteractionPattern> {
+        let mut pattern = PatternState::<u8>::new();
+        pattern.begin_protocol::<()>("example");
+        pattern.interact(Interaction::new::<u8>(
+            Hierarchy::Atomic,
+            Kind::Public,
+            "statement",
+            Length::Fixed(4),
+        ));
+        pattern.interact(Interaction::new::<u8>(
+            Hierarchy::Atomic,
+            Kind::Message,
+            "commitment",
+            Length::Dynamic,
+        ));
+        pattern.interact(Interaction::new::<u8>(
+            Hierarchy::Atomic,
+            Kind::Hint,
+            "hint",
+            Length::Dynamic,
+        ));
+        pattern.interact(Interaction::new::<u8>(
+            Hierarchy::Atomic,
+            Kind::Challenge,
+            "challenge",
+            Length::Fixed(16),
+        ));
+        pattern.end_protocol::<()>("example");
+        Arc::new(pattern.finalize())
+    }
+
+    #[test]
+    fn test_pattern_round_trip() {
+        let pattern = example_pattern();
+
+        let mut prover_state = PatternProver::<DefaultHash>::from(pattern.clone());
+        prover_state.begin_protocol::<()>("example");
+        prover_state.public_units("statement", b"stmt").unwrap();
+        prover_state.add_units_dyn("commitment", b"hello").unwrap();
+        prover_state.hint_bytes("hint", b"world").unwrap();
+        let mut challenge = [0u8; 16];
+        prover_state
+            .fill_challenge_units("challenge", &mut challenge)
+            .unwrap();
+        prover_state.end_protocol::<()>("example");
+        let narg_string = prover_state.finish().unwrap();
+
+        let mut verifier_state = PatternVerifier::<DefaultHash>::new(pattern, &narg_string);
+        verifier_state.begin_protocol::<()>("example");
+        verifier_state.public_units("statement", b"stmt").unwrap();
+        assert_eq!(
+            verifier_state.next_units_vec("commitment").unwrap(),
+            b"hello"
+        );
+        assert_eq!(verifier_state.hint_bytes("hint").unwrap(), b"world");
+        let mut verifier_challenge = [0u8; 16];
+        verifier_state
+            .fill_challenge_units("challenge", &mut verifier_challenge)
+            .unwrap();
+        verifier_state.end_protocol::<()>("example");
+        verifier_state.finish().unwrap();
+        assert_eq!(verifier_challenge, challenge);
+    }
+
+    /// A pattern with `rounds` rounds, each with a message of `length` bytes.
+    fn rounds_pattern(rounds: usize, length: Length) -> Arc<InteractionPattern> {
+        let mut pattern = PatternState::<u8>::new();
+        for i in 0..rounds {
+            pattern.begin_protocol::<()>(format!("round-{i}"));
+            pattern.interact(Interaction::new::<u8>(
+                Hierarchy::Atomic,
+                Kind::Message,
+                "commitment",
+                length,
+            ));
+            pattern.end_protocol::<()>(format!("round-{i}"));
+        }
+        Arc::new(pattern.finalize())
+    }
+
+    fn rounds_proof(pattern: Arc<InteractionPattern>, messages: &[&[u8]]) -> ProofResult<Vec<u8>> {
+        let mut prover_state = PatternProver::<DefaultHash>::from(pattern);
+        for (i, message) in messages.iter().enumerate() {
+            prover_state.begin_protocol::<()>(format!("round-{i}"));
+            prover_state.add_units_dyn("commitment", message)?;
+            prover_state.end_protocol::<()>(format!("round-{i}"));
+        }
+        prover_state.finish()
+    }
+
+    fn rounds_verify(
+        pattern: Arc<InteractionPattern>,
+        rounds: usize,
+        narg_string: &[u8],
+    ) -> ProofResult<Vec<Vec<u8>>> {
+        let mut verifier_state = PatternVerifier::<DefaultHash>::new(pattern, narg_string);
+        let mut messages = Vec::new();
+        for i in 0..rounds {
+            verifier_state.begin_protocol::<()>(format!("round-{i}"));
+            messages.push(verifier_state.next_units_vec("commitment")?);
+            verifier_state.end_protocol::<()>(format!("round-{i}"));
+        }
+        verifier_state.finish()?;
+        Ok(messages)
+    }
+
+    #[test]
+    fn test_runtime_labels_and_lengths() {
+        let messages: [&[u8]; 3] = [b"a", b"bc", b"def"];
+
+        // A dynamic length accepts any runtime length.
+        let pattern = rounds_pattern(3, Length::Dynamic);
+        let narg_string = rounds_proof(pattern.clone(), &messages).unwrap();
+        assert_eq!(rounds_verify(pattern, 3, &narg_string).unwrap(), messages);
+
+        // A recorded length is checked by the prover and the verifier.
+        let pattern = rounds_pattern(3, Length::Runtime(2));
+        assert!(format!("{pattern}").contains("Atomic Message commitment Runtime(2) u8"));
+        let err = rounds_proof(pattern.clone(), &messages).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Received interaction Atomic Message commitment Runtime(1) u8"));
+        let narg_string = rounds_proof(pattern.clone(), &[b"ab", b"cd", b"ef"]).unwrap();
+        assert!(rounds_verify(pattern.clone(), 3, &narg_string).is_ok());
+
+        // A longer length prefix is rejected before reading the message,
+        // a shorter one once read.
+        let other = rounds_pattern(3, Length::Runtime(3));
+        let narg_string = rounds_proof(other, &[b"abc", b"def", b"ghi"]).unwrap();
+        let err = rounds_verify(pattern.clone(), 3, &narg_string).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Message of 3 units is longer than 2"));
+        let other = rounds_pattern(3, Length::Runtime(1));
+        let narg_string = rounds_proof(other, &[b"a", b"b", b"c"]).unwrap();
+        assert!(matches!(
+            rounds_verify(pattern, 3, &narg_string),
+            Err(crate::ProofError::InvalidDomainSeparator(
+                DomainSeparatorMismatch::Pattern(TranscriptError::UnexpectedInteraction {
+                    position: 1,
+                    ..
+                })
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_dynamic_interactions_are_checked_before_reading() {
+        let pattern = example_pattern();
+        let mut prover_state = PatternProver::<DefaultHash>::from(pattern.clone());
+        prover_state.begin_protocol::<()>("example");
+        prover_state.public_units("statement", b"stmt").unwrap();
+        prover_state.add_units_dyn("commitment", b"hello").unwrap();
+        let narg_string = prover_state.narg_string().to_vec();
+
+        // a wrong label or kind is rejected without reading the NARG string
+        let mut verifier_state = PatternVerifier::<DefaultHash>::new(pattern.clone(), &narg_string);
+        verifier_state.begin_protocol::<()>("example");
+        verifier_state.public_units("statement", b"stmt").unwrap();
+        assert!(verifier_state.next_units_vec("other").is_err());
+        assert_eq!(verifier_state.state.narg_string.len(), narg_string.len());
+
+        let mut verifier_state = PatternVerifier::<DefaultHash>::new(pattern, &narg_string);
+        verifier_state.begin_protocol::<()>("example");
+        verifier_state.public_units("statement", b"stmt").unwrap();
+        assert!(verifier_state.hint_bytes("commitment").is_err());
+        assert_eq!(verifier_state.state.narg_string.len(), narg_string.len());
+    }
+
+    #[test]
+    fn test_untyped_patterns_are_rejected() {
+        let pattern = example_pattern();
+        let parsed = Arc::new(
+            format!("{pattern:#}")
+                .parse::<InteractionPattern>()
+                .unwrap(),
+        );
+        assert_eq!(parsed.pattern_hash(), pattern.pattern_hash());
+        assert!(matches!(
+            PatternVerifier::<DefaultHash>::try_new(parsed.clone(), &[]),
+            Err(TranscriptError::Untyped { position: 0, .. })
+        ));
+        assert!(PatternProver::<DefaultHash>::try_new(parsed, DefaultRng::default()).is_err());
+        assert!(PatternVerifier::<DefaultHash>::try_new(pattern, &[]).is_ok());
+    }
+
+    #[test]
+    fn test_iv_is_pattern_hash() {
+        let mut pattern = PatternState::<u8>::new();
+        pattern.interact(Interaction::new::<u8>(
+            Hierarchy::Atomic,
+            Kind::Challenge,
+            "challenge",
+            Length::Fixed(16),
+        ));
+        let pattern = Arc::new(pattern.finalize());
+
+        let mut prover_state = PatternProver::<DefaultHash>::from(pattern.clone());
+        let mut challenge = [0u8; 16];
+        prover_state
+            .fill_challenge_units("challenge", &mut challenge)
+            .unwrap();
+        prover_state.finish().unwrap();
+
+        let mut expected = [0u8; 16];
+        HashStateWithInstructions::<DefaultHash>::unchecked(pattern.pattern_hash())
+            .squeeze(&mut expected)
+            .unwrap();
+        assert_eq!(challenge, expected);
+
+        let domsep = DomainSeparator::<DefaultHash>::new("pattern").squeeze(16, "challenge");
+        let mut domsep_challenge = [0u8; 16];
+        domsep
+            .to_prover_state()
+            .fill_challenge_units(&mut domsep_challenge)
+            .unwrap();
+        assert_ne!(challenge, domsep_challenge);
+    }
+
+    #[test]
+    fn test_length_mismatch() {
+        let mut prover_state =
+            PatternProver::<DefaultHash>::new(example_pattern(), DefaultRng::default());
+        prover_state.begin_protocol::<()>("example");
+        prover_state.public_units("statement", b"stmt").unwrap();
+        // a fixed-length message where the pattern expects a dynamic one
+        let err = prover_state.add_units("commitment", b"hello").unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("but expected Atomic Message commitment Dynamic u8"));
+        // the transcript is unusable afterwards
+        assert!(prover_state.add_units_dyn("commitment", b"hello").is_err());
+        assert!(prover_state.narg_string().is_empty());
+        assert!(prover_state.finish().is_err());
+    }
+
+    #[test]
+    fn test_unfinished() {
+        let narg_string = [];
+        let mut verifier_state =
+            PatternVerifier::<DefaultHash>::new(example_pattern(), &narg_string);
+        verifier_state.begin_protocol::<()>("example");
+        assert!(matches!(
+            verifier_state.finish(),
+            Err(crate::ProofError::InvalidDomainSeparator(
+                DomainSeparatorMismatch::Pattern(TranscriptError::Unfinished { position: 1, .. })
+            ))
+        ));
+
+        // dropping an unfinished transcript is fine
+        let mut verifier_state =
+            PatternVerifier::<DefaultHash>::new(example_pattern(), &narg_string);
+        verifier_state.begin_protocol::<()>("example");
+        assert!(verifier_state.public_units("statement", b"stmt").is_ok());
+        assert!(verifier_state.next_units_vec("commitment").is_err());
+
+        // as is bailing out after a failure
+        assert!(verifier_state.try_end_protocol::<()>("example").is_err());
+        verifier_state.abort();
+        verifier_state.abort();
+    }
+}
@@ -0,0 +1,55 @@
+use alloc::{borrow::Cow, format};
+
+/// Types with a stable identifier in interaction patterns.
+///
+/// Unlike [`core::any::type_name`], the identifier does not depend on the compiler version
+/// or on the path of the type: it is derived from what the values are,
+/// e.g. the modulus of a prime field, so that a pattern recorded by one build
+/// is accepted by another.
+///
+/// The parameter `Family` keeps apart blanket implementations that would otherwise overlap,
+/// e.g. the ones for zkcrypto fields and groups.
+/// Other types implement `TranscriptType<()>`.
+pub trait TranscriptType<Family = ()> {
+    /// The identifier of the type.
+    fn type_id() -> Cow<'static, str>;
+}
+
+macro_rules! impl_transcript_type {
+    ($($t:ty),*) => {
+        $(
+            impl TranscriptType for $t {
+                fn type_id() -> Cow<'static, str> {
+                    Cow::Borrowed(stringify!($t))
+                }
+            }
+        )*
+    };
+}
+
+impl_transcript_type!((), bool, u8, u16, u32, u64, u128);
+
+impl<T: TranscriptType> TranscriptType for [T] {
+    fn type_id() -> Cow<'static, str> {
+        Cow::Owned(format!("[{}]", T::type_id()))
+    }
+}
+
+impl<T: TranscriptType, const N: usize> TranscriptType for [T; N] {
+    fn type_id() -> Cow<'static, str> {
+        Cow::Owned(format!("[{}; {N}]", T::type_id()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_ids() {
+        assert_eq!(<()>::type_id(), "()");
+        assert_eq!(u8::type_id(), "u8");
+        assert_eq!(<[u8]>::type_id(), "[u8]");
+        assert_eq!(<[[u64; 2]; 32]>::type_id(), "[[u64; 2]; 32]");
+    }
+}
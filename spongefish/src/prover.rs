@@ -1,42 +1,64 @@
+use alloc::vec::Vec;
+use core::num::NonZeroUsize;
+
 use rand::{CryptoRng, RngCore};
+use zeroize::Zeroize;
 
 use super::{
-    duplex_sponge::DuplexSpongeInterface, keccak::Keccak, DefaultHash, DefaultRng,
-    DomainSeparatorMismatch,
+    duplex_sponge::DuplexSpongeInterface, keccak::Keccak, DomainSeparatorMismatch, ProofResult,
 };
 use crate::{
-    duplex_sponge::Unit, BytesToUnitSerialize, DomainSeparator, HashStateWithInstructions,
-    UnitTranscript,
+    duplex_sponge::Unit, io, BytesToUnitSerialize, CompiledDomainSeparator, DomainSeparator,
+    HashStateWithInstructions, UnitTranscript,
 };
-
-/// [`ProverState`] is the prover state of an interactive proof (IP) system.
-/// It internally holds the **secret coins** of the prover for zero-knowledge, and
-/// has the hash function state for the verifier state.
-///
-/// Unless otherwise specified,
-/// [`ProverState`] is set to work over bytes with [`DefaultHash`] and
-/// rely on the default random number generator [`DefaultRng`].
-///
-///
-/// # Safety
-///
-/// The prover state is meant to be private in contexts where zero-knowledge is desired.
-/// Leaking the prover state *will* leak the prover's private coins and as such it will compromise the zero-knowledge property.
-/// [`ProverState`] does not implement [`Clone`] or [`Copy`] to prevent accidental leaks.
-pub struct ProverState<H = DefaultHash, U = u8, R = DefaultRng>
-where
-    U: Unit,
-    H: DuplexSpongeInterface<U>,
-    R: RngCore + CryptoRng,
-{
-    /// The randomness state of the prover.
-    pub(crate) rng: ProverPrivateRng<R>,
-    /// The public coins for the protocol
-    pub(crate) hash_state: HashStateWithInstructions<H, U>,
-    /// The encoded data.
-    pub(crate) narg_string: Vec<u8>,
+#[cfg(feature = "std")]
+use crate::{DefaultHash, DefaultRng};
+
+// The default random number generator is only available with `std`,
+// and generic parameters with a default must come last.
+macro_rules! prover_state {
+    ($($generics:tt)*) => {
+        /// [`ProverState`] is the prover state of an interactive proof (IP) system.
+        /// It internally holds the **secret coins** of the prover for zero-knowledge, and
+        /// has the hash function state for the verifier state.
+        ///
+        /// Unless otherwise specified,
+        /// [`ProverState`] is set to work over bytes with [`DefaultHash`] and
+        /// rely on the default random number generator [`DefaultRng`].
+        /// Without the `std` feature there is no default source of entropy:
+        /// the random number generator `R`, and thus `H` and `U`, must always be specified.
+        /// The private coins are generated with [`Keccak`], unless another byte-oriented sponge `P` is specified
+        /// (e.g., to ship a single permutation on constrained targets).
+        /// The NARG string is kept in memory, unless it is streamed to a writer `W` with [`ProverState::with_writer`].
+        ///
+        ///
+        /// # Safety
+        ///
+        /// The prover state is meant to be private in contexts where zero-knowledge is desired.
+        /// Leaking the prover state *will* leak the prover's private coins and as such it will compromise the zero-knowledge property.
+        /// [`ProverState`] does not implement [`Clone`] or [`Copy`] to prevent accidental leaks.
+        pub struct ProverState<$($generics)*>
+        where
+            U: Unit,
+            H: DuplexSpongeInterface<U>,
+            R: RngCore + CryptoRng,
+            P: DuplexSpongeInterface,
+        {
+            /// The randomness state of the prover.
+            pub(crate) rng: ProverPrivateRng<R, P>,
+            /// The public coins for the protocol
+            pub(crate) hash_state: HashStateWithInstructions<H, U>,
+            /// The encoded data.
+            pub(crate) narg_string: W,
+        }
+    };
 }
 
+#[cfg(feature = "std")]
+prover_state!(H = DefaultHash, U = u8, R = DefaultRng, P = Keccak, W = Vec<u8>);
+#[cfg(not(feature = "std"))]
+prover_state!(H, U, R, P = Keccak, W = Vec<u8>);
+
 /// A cryptographically-secure random number generator that is bound to the protocol transcript.
 ///
 /// For most public-coin protocols it is *vital* not to have two different verifier messages for the same prover message.
@@ -44,14 +66,84 @@ where
 /// it is seeded by a cryptographic random number generator (by default, [`rand::rngs::OsRng`]).
 ///
 /// Every time a challenge is being generated, the private prover sponge is ratcheted, so that it can't be inverted and the randomness recovered.
-pub struct ProverPrivateRng<R: RngCore + CryptoRng> {
+///
+/// The private sponge `P` is independent of the sponge used for the public coins,
+/// and absorbs the same prover messages, as encoded in the NARG string.
+pub struct ProverPrivateRng<R: RngCore + CryptoRng, P: DuplexSpongeInterface = Keccak> {
     /// The duplex sponge that is used to generate the random coins.
-    pub(crate) ds: Keccak,
+    pub(crate) ds: P,
     /// The cryptographic random number generator that seeds the sponge.
     pub(crate) csrng: R,
+    /// When, and how much, entropy is drawn from `csrng`.
+    pub(crate) policy: ReseedPolicy,
+    /// The number of outputs produced so far.
+    pub(crate) calls: usize,
+}
+
+/// The reseeding policy of the prover's private coins.
+///
+/// Every `interval` outputs (starting from the first one), `entropy` bytes are drawn from the CSRNG
+/// and absorbed in the private sponge before squeezing.
+/// Regardless of the policy, the private sponge is ratcheted after each output,
+/// so that earlier outputs can't be recovered from the current state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReseedPolicy {
+    /// The number of outputs between two reseeds.
+    pub interval: NonZeroUsize,
+    /// The number of bytes drawn from the CSRNG at each reseed.
+    pub entropy: usize,
 }
 
-impl<R: RngCore + CryptoRng> RngCore for ProverPrivateRng<R> {
+impl ReseedPolicy {
+    /// Never draw entropy from the CSRNG.
+    pub const NEVER: Self = Self {
+        interval: NonZeroUsize::MIN,
+        entropy: 0,
+    };
+}
+
+impl Default for ReseedPolicy {
+    /// Reseed with 32 bytes of entropy at every output.
+    fn default() -> Self {
+        Self {
+            interval: NonZeroUsize::MIN,
+            entropy: 32,
+        }
+    }
+}
+
+/// The random number generator of [`ProverState::new_deterministic`], which provides no entropy at all.
+///
+/// The private coins of the prover are then derived only from the witness
+/// and from the protocol transcript, in the spirit of RFC 6979.
+/// It should not be passed to [`ProverState::new`], where no secret is absorbed:
+/// the private coins would be predictable from the transcript.
+#[derive(Clone, Copy, Debug)]
+pub struct DeterministicRng;
+
+impl RngCore for DeterministicRng {
+    fn next_u32(&mut self) -> u32 {
+        0
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        0
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        dest.fill(0);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+// The private coins are as secret as the witness they are derived from.
+impl CryptoRng for DeterministicRng {}
+
+impl<R: RngCore + CryptoRng, P: DuplexSpongeInterface> RngCore for ProverPrivateRng<R, P> {
     fn next_u32(&mut self) -> u32 {
         let mut buf = [0u8; 4];
         self.fill_bytes(buf.as_mut());
@@ -65,36 +157,105 @@ impl<R: RngCore + CryptoRng> RngCore for ProverPrivateRng<R> {
     }
 
     fn fill_bytes(&mut self, dest: &mut [u8]) {
-        // Seed (at most) 32 bytes of randomness from the CSRNG
-        let len = usize::min(dest.len(), 32);
-        self.csrng.fill_bytes(&mut dest[..len]);
-        self.ds.absorb_unchecked(&dest[..len]);
+        self.try_fill_bytes(dest)
+            .unwrap_or_else(|e| panic!("CSRNG failure: {e}"));
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        if self.calls % self.policy.interval == 0 {
+            self.reseed()?;
+        }
+        self.calls = self.calls.wrapping_add(1);
         // fill `dest` with the output of the sponge
         self.ds.squeeze_unchecked(dest);
         // erase the state from the sponge so that it can't be reverted
         self.ds.ratchet_unchecked();
+        Ok(())
     }
+}
 
-    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
-        self.ds.squeeze_unchecked(dest);
+impl<R: RngCore + CryptoRng, P: DuplexSpongeInterface> ProverPrivateRng<R, P> {
+    /// Absorb secret material in the private sponge, prefixed with its length.
+    pub(crate) fn absorb_secret(&mut self, secret: &[u8]) {
+        self.ds
+            .absorb_unchecked(&(secret.len() as u64).to_le_bytes());
+        self.ds.absorb_unchecked(secret);
+    }
+
+    /// Absorb `policy.entropy` bytes from the CSRNG into the private sponge.
+    fn reseed(&mut self) -> Result<(), rand::Error> {
+        let mut seed = [0u8; 32];
+        let mut remaining = self.policy.entropy;
+        while remaining > 0 {
+            let len = usize::min(remaining, seed.len());
+            self.csrng.try_fill_bytes(&mut seed[..len])?;
+            self.ds.absorb_unchecked(&seed[..len]);
+            remaining -= len;
+        }
+        seed.zeroize();
         Ok(())
     }
 }
 
-impl<H, U, R> ProverState<H, U, R>
+impl<H, U, R, P> ProverState<H, U, R, P>
 where
     U: Unit,
     H: DuplexSpongeInterface<U>,
     R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
 {
-    pub fn new(domain_separator: &DomainSeparator<H, U>, csrng: R) -> Self {
+    /// Create a new prover state generating its private coins with the sponge `P`.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    /// # use rand::RngCore;
+    /// use spongefish::duplex_sponge::legacy::DigestBridge;
+    ///
+    /// let domain_separator = DomainSeparator::<DefaultHash>::new("📝");
+    /// let mut prover_state = ProverState::<_, _, _, DigestBridge<sha2::Sha256>>::with_private_sponge(
+    ///     &domain_separator,
+    ///     DefaultRng::default(),
+    /// );
+    /// assert_ne!(prover_state.rng().next_u64(), 0);
+    /// ```
+    pub fn with_private_sponge(domain_separator: &DomainSeparator<H, U>, csrng: R) -> Self {
         let hash_state = HashStateWithInstructions::new(domain_separator);
+        Self::with_hash_state(hash_state, domain_separator.as_bytes(), csrng)
+    }
+
+    /// Create a new prover state from a compiled domain separator,
+    /// generating its private coins with the sponge `P`.
+    ///
+    /// This is equivalent to [`ProverState::with_private_sponge`] with the original domain separator,
+    /// but the domain separator is not parsed and hashed again.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let compiled = DomainSeparator::<DefaultHash>::new("📝").absorb(1, "🥚").compile();
+    /// let mut prover_state: ProverState = ProverState::from_compiled(&compiled, DefaultRng::default());
+    /// prover_state.add_bytes(b"!").unwrap();
+    /// assert_eq!(prover_state.narg_string(), b"!");
+    /// ```
+    pub fn from_compiled(compiled: &CompiledDomainSeparator<H, U>, csrng: R) -> Self {
+        let hash_state = HashStateWithInstructions::from_compiled(compiled);
+        Self::with_hash_state(hash_state, compiled.domain_separator().as_bytes(), csrng)
+    }
 
-        let mut duplex_sponge = Keccak::default();
-        duplex_sponge.absorb_unchecked(domain_separator.as_bytes());
+    /// Create a new prover state from its public sponge,
+    /// seeding the private sponge with `protocol_id` (e.g. the domain separator).
+    pub(crate) fn with_hash_state(
+        hash_state: HashStateWithInstructions<H, U>,
+        protocol_id: &[u8],
+        csrng: R,
+    ) -> Self {
+        let mut duplex_sponge = P::default();
+        duplex_sponge.absorb_unchecked(protocol_id);
         let rng = ProverPrivateRng {
             ds: duplex_sponge,
             csrng,
+            policy: ReseedPolicy::default(),
+            calls: 0,
         };
 
         Self {
@@ -104,15 +265,175 @@ where
         }
     }
 
+    /// Stream the NARG string into `writer` instead of keeping it in memory.
+    ///
+    /// Prover messages are written as soon as they are added,
+    /// starting with the ones added so far.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domain_separator = DomainSeparator::<DefaultHash>::new("📝").absorb(3, "🍝").hint("🧂");
+    /// // Any `std::io::Write` works, e.g. a file or a socket.
+    /// let mut prover_state = domain_separator.to_prover_state().with_writer(Vec::new()).unwrap();
+    /// prover_state.add_bytes(b"abc").unwrap();
+    /// prover_state.hint_bytes(b"salt").unwrap();
+    /// let narg_string = prover_state.finish().unwrap();
+    ///
+    /// let mut verifier_state = domain_separator.to_verifier_state(&narg_string);
+    /// assert_eq!(verifier_state.next_bytes().unwrap(), *b"abc");
+    /// assert_eq!(verifier_state.hint_bytes().unwrap(), b"salt");
+    /// ```
+    pub fn with_writer<W: io::Write>(
+        self,
+        mut writer: W,
+    ) -> Result<ProverState<H, U, R, P, W>, DomainSeparatorMismatch> {
+        writer.write_all(&self.narg_string)?;
+        Ok(ProverState {
+            rng: self.rng,
+            hash_state: self.hash_state,
+            narg_string: writer,
+        })
+    }
+
+    /// Return the current protocol transcript.
+    /// The protocol transcript does not have any information about the length or the type of the messages being read.
+    /// This is because the information is considered pre-shared within the [`DomainSeparator`].
+    /// Additionally, since the verifier challenges are deterministically generated from the prover's messages,
+    /// the transcript does not hold any of the verifier's messages.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domain_separator = DomainSeparator::<DefaultHash>::new("📝").absorb(8, "how to make pasta 🤌");
+    /// let mut prover_state = domain_separator.to_prover_state();
+    /// prover_state.add_bytes(b"1tbsp:3l").unwrap();
+    /// assert_eq!(prover_state.narg_string(), b"1tbsp:3l");
+    /// ```
+    pub fn narg_string(&self) -> &[u8] {
+        self.narg_string.as_slice()
+    }
+}
+
+impl<H, U, R, P, W> ProverState<H, U, R, P, W>
+where
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+    R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
+{
+    /// Set the [`ReseedPolicy`] of the prover's private coins.
+    #[must_use]
+    pub const fn with_reseed_policy(mut self, policy: ReseedPolicy) -> Self {
+        self.rng.policy = policy;
+        self
+    }
+
+    /// Absorb secret material (e.g., the witness) in the prover's private coins.
+    ///
+    /// The secret is neither absorbed in the public sponge nor written to the NARG string.
+    /// It makes the private coins unpredictable even if the CSRNG is weak (hedged nonces).
+    ///
+    /// ```
+    /// # use spongefish::*;
+    /// # use rand::RngCore;
+    ///
+    /// let domain_separator = DomainSeparator::<DefaultHash>::new("📝");
+    /// let mut prover_state = domain_separator.to_prover_state();
+    /// prover_state.absorb_secret(b"my secret key");
+    /// assert_ne!(prover_state.rng().next_u64(), 0);
+    /// assert_eq!(prover_state.narg_string(), b"");
+    /// ```
+    pub fn absorb_secret(&mut self, secret: &[u8]) {
+        self.rng.absorb_secret(secret);
+    }
+
     pub fn hint_bytes(&mut self, hint: &[u8]) -> Result<(), DomainSeparatorMismatch> {
-        self.hash_state.hint()?;
-        let len = u32::try_from(hint.len()).expect("Hint size out of bounds");
-        self.narg_string.extend_from_slice(&len.to_le_bytes());
-        self.narg_string.extend_from_slice(hint);
-        Ok(())
+        let len = u32::try_from(hint.len())
+            .map_err(|_| DomainSeparatorMismatch::from("Hint size out of bounds"))?;
+        self.hash_state.hint(hint.len())?;
+        self.write_narg(&len.to_le_bytes())?;
+        self.write_narg(hint)
+    }
+
+    /// Write already absorbed bytes to the NARG string.
+    ///
+    /// Together with the encoding of prover messages, this is the only place where
+    /// the NARG string is written, whether it is kept in memory or streamed.
+    pub(crate) fn write_narg(&mut self, bytes: &[u8]) -> Result<(), DomainSeparatorMismatch> {
+        Ok(self.narg_string.write_all(bytes)?)
+    }
+}
+
+impl<H, U, R> ProverState<H, U, R>
+where
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+    R: RngCore + CryptoRng,
+{
+    pub fn new(domain_separator: &DomainSeparator<H, U>, csrng: R) -> Self {
+        Self::with_private_sponge(domain_separator, csrng)
+    }
+
+    /// Create a hedged prover state: the private coins are derived from the witness, the
+    /// protocol transcript, *and* the randomness of `csrng`.
+    ///
+    /// Compared to [`ProverState::new`], the private coins remain unpredictable
+    /// even if `csrng` is weak or compromised, as long as the witness is secret.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    /// # use rand::RngCore;
+    ///
+    /// let domain_separator = DomainSeparator::<DefaultHash>::new("📝");
+    /// let witness = b"my secret key";
+    /// let mut prover_state = ProverState::new_hedged(&domain_separator, witness, DefaultRng::default());
+    /// assert_ne!(prover_state.rng().next_u64(), 0);
+    /// ```
+    pub fn new_hedged(domain_separator: &DomainSeparator<H, U>, witness: &[u8], csrng: R) -> Self {
+        let mut prover_state = Self::new(domain_separator, csrng);
+        prover_state.absorb_secret(witness);
+        prover_state
+    }
+}
+
+impl<H, U> ProverState<H, U, DeterministicRng>
+where
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+{
+    /// Create a deterministic prover state: the private coins are derived from the witness
+    /// and from the protocol transcript only.
+    ///
+    /// The same domain separator, witness, and prover messages always lead to the same NARG string.
+    /// This is useful for reproducible proofs and test vectors.
+    /// The witness **must** contain all the secret information of the prover,
+    /// otherwise the private coins can be predicted (and, e.g., a Schnorr secret key recovered).
+    /// Prefer [`ProverState::new_hedged`] outside of testing.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    /// # use rand::RngCore;
+    ///
+    /// let domain_separator = DomainSeparator::<DefaultHash>::new("📝").absorb(8, "nonce");
+    /// let witness = b"my secret key";
+    /// let proofs = [0, 1].map(|_| {
+    ///     let mut prover_state = ProverState::new_deterministic(&domain_separator, witness);
+    ///     let nonce = prover_state.rng().next_u64();
+    ///     prover_state.add_bytes(&nonce.to_le_bytes()).unwrap();
+    ///     prover_state.narg_string().to_vec()
+    /// });
+    /// assert_eq!(proofs[0], proofs[1]);
+    /// ```
+    #[must_use]
+    pub fn new_deterministic(domain_separator: &DomainSeparator<H, U>, witness: &[u8]) -> Self {
+        Self::new_hedged(domain_separator, witness, DeterministicRng)
+            .with_reseed_policy(ReseedPolicy::NEVER)
     }
 }
 
+#[cfg(feature = "std")]
 impl<U, H> From<&DomainSeparator<H, U>> for ProverState<H, U, DefaultRng>
 where
     U: Unit,
@@ -123,11 +444,13 @@ where
     }
 }
 
-impl<H, U, R> ProverState<H, U, R>
+impl<H, U, R, P, W> ProverState<H, U, R, P, W>
 where
     U: Unit,
     H: DuplexSpongeInterface<U>,
     R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
 {
     /// Add a slice `[U]` to the protocol transcript.
     /// The messages are also internally encoded in the protocol transcript,
@@ -143,13 +466,52 @@ where
     /// assert!(result.is_err())
     /// ```
     pub fn add_units(&mut self, input: &[U]) -> Result<(), DomainSeparatorMismatch> {
-        let old_len = self.narg_string.len();
-        self.hash_state.absorb(input)?;
-        // write never fails on Vec<u8>
-        U::write(input, &mut self.narg_string).unwrap();
-        self.rng.ds.absorb_unchecked(&self.narg_string[old_len..]);
+        self.absorb_units(input, true)
+    }
 
-        Ok(())
+    /// Add a variable-length slice `[U]` to the protocol transcript, prefixed by its length.
+    ///
+    /// The length is absorbed and written in the NARG string as 4 little-endian bytes,
+    /// followed by the messages as in [`Self::add_units`].
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domain_separator = DomainSeparator::<DefaultHash>::new("📝").absorb_dyn("📜");
+    /// let mut prover_state = domain_separator.to_prover_state();
+    /// prover_state.add_units_dyn(b"as long as you want").unwrap();
+    /// assert_eq!(prover_state.narg_string().len(), 4 + 19);
+    /// ```
+    pub fn add_units_dyn(&mut self, input: &[U]) -> Result<(), DomainSeparatorMismatch>
+    where
+        U: From<u8>,
+    {
+        let len = u32::try_from(input.len())
+            .map_err(|_| DomainSeparatorMismatch::from("Message size out of bounds"))?;
+        self.hash_state.absorb_dyn_len(len)?;
+        let prefix = len.to_le_bytes();
+        self.rng.ds.absorb_unchecked(&prefix);
+        self.write_narg(&prefix)?;
+        if input.is_empty() {
+            return Ok(());
+        }
+        self.add_units(input)
+    }
+
+    /// Absorb `input` in both sponges, and write its encoding to the NARG string if `to_narg_string`.
+    ///
+    /// The encoding is absorbed in the private sponge as it is written, without buffering it.
+    fn absorb_units(
+        &mut self,
+        input: &[U],
+        to_narg_string: bool,
+    ) -> Result<(), DomainSeparatorMismatch> {
+        self.hash_state.absorb(input)?;
+        let mut sink = EncodingSink {
+            sponge: &mut self.rng.ds,
+            narg_string: to_narg_string.then_some(&mut self.narg_string),
+        };
+        Ok(U::write(input, &mut sink)?)
     }
 
     /// Ratchet the verifier's state.
@@ -175,30 +537,31 @@ where
         &mut self.rng
     }
 
-    /// Return the current protocol transcript.
-    /// The protocol transcript does not have any information about the length or the type of the messages being read.
-    /// This is because the information is considered pre-shared within the [`DomainSeparator`].
-    /// Additionally, since the verifier challenges are deterministically generated from the prover's messages,
-    /// the transcript does not hold any of the verifier's messages.
+    /// Consume the prover state and return the NARG string (or the writer it was streamed to),
+    /// failing if some operations of the domain separator have not been performed.
     ///
     /// ```
     /// # use spongefish::*;
     ///
-    /// let domain_separator = DomainSeparator::<DefaultHash>::new("📝").absorb(8, "how to make pasta 🤌");
+    /// let domain_separator = DomainSeparator::<DefaultHash>::new("📝").absorb(1, "🍝").squeeze(1, "🧂");
     /// let mut prover_state = domain_separator.to_prover_state();
-    /// prover_state.add_bytes(b"1tbsp:3l").unwrap();
-    /// assert_eq!(prover_state.narg_string(), b"1tbsp:3l");
+    /// prover_state.add_bytes(b"x").unwrap();
+    /// // the verifier's challenge has not been squeezed.
+    /// assert!(prover_state.finish().is_err());
     /// ```
-    pub fn narg_string(&self) -> &[u8] {
-        self.narg_string.as_slice()
+    pub fn finish(self) -> ProofResult<W> {
+        self.hash_state.finish()?;
+        Ok(self.narg_string)
     }
 }
 
-impl<H, U, R> UnitTranscript<U> for ProverState<H, U, R>
+impl<H, U, R, P, W> UnitTranscript<U> for ProverState<H, U, R, P, W>
 where
     U: Unit,
     H: DuplexSpongeInterface<U>,
     R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
 {
     /// Add public messages to the protocol transcript.
     /// Messages input to this function are not added to the protocol transcript.
@@ -213,10 +576,7 @@ where
     /// assert_eq!(prover_state.narg_string(), b"");
     /// ```
     fn public_units(&mut self, input: &[U]) -> Result<(), DomainSeparatorMismatch> {
-        let len = self.narg_string.len();
-        self.add_units(input)?;
-        self.narg_string.truncate(len);
-        Ok(())
+        self.absorb_units(input, false)
     }
 
     /// Fill a slice with uniformly-distributed challenges from the verifier.
@@ -225,23 +585,65 @@ where
     }
 }
 
-impl<R: RngCore + CryptoRng> CryptoRng for ProverPrivateRng<R> {}
+impl<R: RngCore + CryptoRng, P: DuplexSpongeInterface> CryptoRng for ProverPrivateRng<R, P> {}
+
+/// The writer prover messages are encoded into: the encoding is absorbed in the private sponge,
+/// and written to the NARG string if any.
+struct EncodingSink<'a, P, W> {
+    sponge: &'a mut P,
+    narg_string: Option<&'a mut W>,
+}
 
-impl<H, U, R> core::fmt::Debug for ProverState<H, U, R>
+impl<P: DuplexSpongeInterface, W: io::Write> EncodingSink<'_, P, W> {
+    fn absorb(&mut self, buf: &[u8]) -> Result<(), io::Error> {
+        self.sponge.absorb_unchecked(buf);
+        self.narg_string
+            .as_mut()
+            .map_or(Ok(()), |narg_string| narg_string.write_all(buf))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<P: DuplexSpongeInterface, W: io::Write> io::Write for EncodingSink<'_, P, W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, io::Error> {
+        self.absorb(buf)?;
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), io::Error> {
+        self.absorb(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        self.narg_string.as_mut().map_or(Ok(()), io::Write::flush)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<P: DuplexSpongeInterface, W: io::Write> io::Write for EncodingSink<'_, P, W> {
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), io::Error> {
+        self.absorb(buf)
+    }
+}
+
+impl<H, U, R, P, W> core::fmt::Debug for ProverState<H, U, R, P, W>
 where
     U: Unit,
     H: DuplexSpongeInterface<U>,
     R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
 {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.hash_state.fmt(f)
     }
 }
 
-impl<H, R> BytesToUnitSerialize for ProverState<H, u8, R>
+impl<H, R, P, W> BytesToUnitSerialize for ProverState<H, u8, R, P, W>
 where
     H: DuplexSpongeInterface<u8>,
     R: RngCore + CryptoRng,
+    P: DuplexSpongeInterface,
+    W: io::Write,
 {
     fn add_bytes(&mut self, input: &[u8]) -> Result<(), DomainSeparatorMismatch> {
         self.add_units(input)
@@ -251,6 +653,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{CommonUnitToBytes, UnitToBytes};
 
     #[test]
     fn test_prover_state_add_units_and_rng_differs() {
@@ -357,6 +760,205 @@ mod tests {
         assert_ne!(a, b);
     }
 
+    #[test]
+    fn test_deterministic_prover_is_reproducible() {
+        let domsep = DomainSeparator::<DefaultHash>::new("t").absorb(16, "nonce");
+        let prove = |witness: &[u8]| {
+            let mut p = ProverState::new_deterministic(&domsep, witness);
+            let mut nonce = [0u8; 16];
+            p.rng().fill_bytes(&mut nonce);
+            p.add_units(&nonce).unwrap();
+            p.finish().unwrap()
+        };
+
+        assert_eq!(prove(b"witness"), prove(b"witness"));
+        assert_ne!(prove(b"witness"), prove(b"witnesS"));
+    }
+
+    #[test]
+    fn test_hedged_prover_depends_on_witness_and_csrng() {
+        let domsep = DomainSeparator::<DefaultHash>::new("t");
+        let mut a = [0u8; 16];
+        let mut b = [0u8; 16];
+
+        ProverState::new_hedged(&domsep, b"witness", DefaultRng::default())
+            .rng()
+            .fill_bytes(&mut a);
+        ProverState::new_hedged(&domsep, b"witness", DefaultRng::default())
+            .rng()
+            .fill_bytes(&mut b);
+        assert_ne!(a, b);
+
+        // with no entropy, the witness alone determines the private coins
+        ProverState::new_hedged(&domsep, b"witness", DeterministicRng)
+            .with_reseed_policy(ReseedPolicy::NEVER)
+            .rng()
+            .fill_bytes(&mut a);
+        ProverState::new_deterministic(&domsep, b"witness")
+            .rng()
+            .fill_bytes(&mut b);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_absorb_secret_only_affects_private_coins() {
+        let domsep = DomainSeparator::<DefaultHash>::new("t")
+            .absorb(1, "a")
+            .squeeze(16, "c");
+        let run = |secret: &[u8]| {
+            let mut p = ProverState::new_deterministic(&domsep, b"");
+            p.absorb_secret(secret);
+            p.add_units(&[42]).unwrap();
+            let mut challenge = [0u8; 16];
+            p.fill_challenge_units(&mut challenge).unwrap();
+            let mut coins = [0u8; 16];
+            p.rng().fill_bytes(&mut coins);
+            (p.finish().unwrap(), challenge, coins)
+        };
+
+        let (narg_a, challenge_a, coins_a) = run(b"secret a");
+        let (narg_b, challenge_b, coins_b) = run(b"secret b");
+        assert_eq!(narg_a, narg_b);
+        assert_eq!(challenge_a, challenge_b);
+        assert_ne!(coins_a, coins_b);
+    }
+
+    /// A CSRNG counting the bytes drawn from it.
+    #[derive(Default)]
+    struct CountingRng(usize);
+
+    impl RngCore for CountingRng {
+        fn next_u32(&mut self) -> u32 {
+            self.0 += 4;
+            0x4242_4242
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0 += 8;
+            0x4242_4242_4242_4242
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            self.0 += dest.len();
+            dest.fill(0x42);
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for CountingRng {}
+
+    #[test]
+    fn test_try_fill_bytes_matches_fill_bytes() {
+        let domsep = DomainSeparator::<DefaultHash>::new("t");
+        let mut p1 = ProverState::new_hedged(&domsep, b"witness", CountingRng::default());
+        let mut p2 = ProverState::new_hedged(&domsep, b"witness", CountingRng::default());
+
+        for _ in 0..3 {
+            let mut a = [0u8; 16];
+            let mut b = [0u8; 16];
+            p1.rng.fill_bytes(&mut a);
+            p2.rng.try_fill_bytes(&mut b).unwrap();
+            assert_eq!(a, b);
+        }
+        assert_eq!(p1.rng.csrng.0, 3 * 32);
+        assert_eq!(p2.rng.csrng.0, 3 * 32);
+    }
+
+    #[test]
+    fn test_reseed_policy() {
+        let domsep = DomainSeparator::<DefaultHash>::new("t");
+        let policy = ReseedPolicy {
+            interval: NonZeroUsize::new(3).unwrap(),
+            entropy: 100,
+        };
+        let mut p = ProverState::new(&domsep, CountingRng::default()).with_reseed_policy(policy);
+
+        let mut buf = [0u8; 8];
+        let drawn = (0..7)
+            .map(|_| {
+                p.rng().fill_bytes(&mut buf);
+                p.rng.csrng.0
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(drawn, [100, 100, 100, 200, 200, 200, 300]);
+
+        let mut p = ProverState::new(&domsep, CountingRng::default())
+            .with_reseed_policy(ReseedPolicy::NEVER);
+        p.rng().fill_bytes(&mut buf);
+        assert_eq!(p.rng.csrng.0, 0);
+    }
+
+    #[test]
+    fn test_ratchet_makes_outputs_unrecoverable() {
+        let domsep = DomainSeparator::<DefaultHash>::new("t");
+        let mut p = ProverState::new_deterministic(&domsep, b"witness");
+
+        let before = p.rng.ds.tag();
+        let mut output = [0u8; 32];
+        p.rng().fill_bytes(&mut output);
+        let after = p.rng.ds.tag();
+        assert_ne!(before, after);
+
+        // the rate has been erased: the state is fully described by the capacity
+        let mut from_capacity = Keccak::load(&after).unwrap();
+        let mut a = [0u8; 32];
+        from_capacity.squeeze_unchecked(&mut a);
+        let mut next = [0u8; 32];
+        p.rng().fill_bytes(&mut next);
+        assert_eq!(a, next);
+        assert_ne!(next, output);
+
+        // and the capacity before ratcheting does not lead to the same stream
+        let mut from_before = Keccak::load(&before).unwrap();
+        let mut b = [0u8; 32];
+        from_before.squeeze_unchecked(&mut b);
+        assert_ne!(b, next);
+        assert_ne!(b, output);
+
+        // pin the private coins of the deterministic prover
+        assert_eq!(
+            hex::encode(output),
+            "af9f647ab4900abad48b46a468eb9fac9e4662d9cac63ce18a127d2ce6c94b83"
+        );
+        assert_eq!(
+            hex::encode(next),
+            "067a27afb9deef8e346739099ff6e2252b08c6bf24667df4c61082210a116ab8"
+        );
+    }
+
+    #[test]
+    fn test_private_sponge_is_pluggable() {
+        type P = crate::DigestBridge<sha2::Sha256>;
+        let domsep = DomainSeparator::<DefaultHash>::new("t")
+            .absorb(3, "a")
+            .squeeze(8, "c");
+        let run = |msg: &[u8]| {
+            let mut p = ProverState::<_, _, _, P>::with_private_sponge(&domsep, DeterministicRng)
+                .with_reseed_policy(ReseedPolicy::NEVER);
+            p.add_units(msg).unwrap();
+            let mut challenge = [0u8; 8];
+            p.fill_challenge_units(&mut challenge).unwrap();
+            let mut coins = [0u8; 8];
+            p.rng().fill_bytes(&mut coins);
+            (challenge, coins)
+        };
+
+        // the public coins do not depend on the private sponge
+        let mut keccak_prover = ProverState::new_deterministic(&domsep, b"");
+        keccak_prover.add_units(b"abc").unwrap();
+        let mut challenge = [0u8; 8];
+        keccak_prover.fill_challenge_units(&mut challenge).unwrap();
+        assert_eq!(run(b"abc").0, challenge);
+
+        // the private sponge absorbs the prover messages
+        assert_eq!(run(b"abc").1, run(b"abc").1);
+        assert_ne!(run(b"abc").1, run(b"abd").1);
+    }
+
     #[test]
     fn test_add_units_multiple_accumulates() {
         let domsep = DomainSeparator::<DefaultHash>::new("t")
@@ -442,4 +1044,64 @@ mod tests {
             "Encoding should be deterministic"
         );
     }
+
+    #[test]
+    fn test_with_writer_streams_the_narg_string() {
+        let domsep = DomainSeparator::<DefaultHash>::new("stream")
+            .absorb(2, "a")
+            .squeeze(4, "c")
+            .absorb(1, "p")
+            .absorb(3, "b")
+            .hint("h");
+
+        let mut in_memory = ProverState::new_deterministic(&domsep, b"witness");
+        in_memory.add_bytes(b"ab").unwrap();
+        let chal = in_memory.challenge_bytes::<4>().unwrap();
+        in_memory.public_bytes(b"p").unwrap();
+        in_memory.add_bytes(b"cde").unwrap();
+        in_memory.hint_bytes(b"hint").unwrap();
+
+        // Switch to streaming after the first message.
+        let mut streaming = ProverState::new_deterministic(&domsep, b"witness");
+        streaming.add_bytes(b"ab").unwrap();
+        let mut streaming = streaming
+            .with_writer(std::io::Cursor::new(Vec::new()))
+            .unwrap();
+        assert_eq!(streaming.challenge_bytes::<4>().unwrap(), chal);
+        streaming.public_bytes(b"p").unwrap();
+        streaming.add_bytes(b"cde").unwrap();
+        streaming.hint_bytes(b"hint").unwrap();
+
+        assert_eq!(
+            in_memory.rng().next_u64(),
+            streaming.rng().next_u64(),
+            "private coins should not depend on the sink"
+        );
+        assert_eq!(
+            streaming.finish().unwrap().into_inner(),
+            in_memory.finish().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_with_writer_reports_io_errors() {
+        struct FullSink;
+
+        impl std::io::Write for FullSink {
+            fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::WriteZero.into())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let domsep = DomainSeparator::<DefaultHash>::new("stream").absorb(1, "a");
+        let mut prover = domsep.to_prover_state().with_writer(FullSink).unwrap();
+        assert!(matches!(
+            prover.add_bytes(b"a"),
+            Err(DomainSeparatorMismatch::Io(_))
+        ));
+    }
 }
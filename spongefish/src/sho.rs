@@ -1,13 +1,56 @@
+use alloc::{collections::vec_deque::VecDeque, string::ToString, sync::Arc, vec::Vec};
 use core::{fmt, marker::PhantomData};
-use std::collections::vec_deque::VecDeque;
+#[cfg(feature = "std")]
+use std::sync::RwLock;
 
 use super::{
-    domain_separator::{DomainSeparator, Op},
+    domain_separator::{DomainSeparator, LabeledOp, Op},
     duplex_sponge::{DuplexSpongeInterface, Unit},
     errors::DomainSeparatorMismatch,
     keccak::Keccak,
 };
 
+/// The action taken when a [`HashStateWithInstructions`] is dropped
+/// before all operations of the domain separator have been performed.
+///
+/// Use [`HashStateWithInstructions::finish`] (or `finish` on the prover and verifier states)
+/// to get an error instead.
+///
+/// The policy is set process-wide with [`set_unfinished_policy`], which requires the `std` feature;
+/// without it, unfinished states are dropped silently.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnfinishedPolicy {
+    /// Print the remaining operations to the standard error.
+    #[default]
+    Warn,
+    /// Panic in debug builds, and do nothing in release builds.
+    DebugPanic,
+    /// Do nothing.
+    Silent,
+    /// Call the given function with the [`DomainSeparatorMismatch::Unfinished`] error.
+    Hook(fn(&DomainSeparatorMismatch)),
+}
+
+#[cfg(feature = "std")]
+static UNFINISHED_POLICY: RwLock<UnfinishedPolicy> = RwLock::new(UnfinishedPolicy::Warn);
+
+/// Set the process-wide [`UnfinishedPolicy`], returning the previous one.
+#[cfg(feature = "std")]
+pub fn set_unfinished_policy(policy: UnfinishedPolicy) -> UnfinishedPolicy {
+    let mut current = UNFINISHED_POLICY
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    core::mem::replace(&mut *current, policy)
+}
+
+/// Return the process-wide [`UnfinishedPolicy`].
+#[cfg(feature = "std")]
+pub fn unfinished_policy() -> UnfinishedPolicy {
+    *UNFINISHED_POLICY
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
 /// A stateful hash object that interfaces with duplex interfaces.
 #[derive(Clone)]
 pub struct HashStateWithInstructions<H, U = u8>
@@ -18,7 +61,12 @@ where
     /// The internal duplex sponge used for absorbing and squeezing data.
     ds: H,
     /// A stack of expected sponge operations.
-    stack: VecDeque<Op>,
+    stack: VecDeque<LabeledOp>,
+    /// Whether operations are checked against the stack.
+    ///
+    /// Transcripts following an [`InteractionPattern`](crate::pattern::InteractionPattern)
+    /// are checked by their [`PatternPlayer`](crate::pattern::PatternPlayer) instead.
+    checked: bool,
     /// Marker to associate the unit type `U` without storing a value.
     _unit: PhantomData<U>,
 }
@@ -28,68 +76,168 @@ impl<U: Unit, H: DuplexSpongeInterface<U>> HashStateWithInstructions<H, U> {
     /// setting up the state of the sponge function and parsing the tag string.
     #[must_use]
     pub fn new(domain_separator: &DomainSeparator<H, U>) -> Self {
-        let stack = domain_separator.finalize();
+        let stack = domain_separator.labeled_ops();
         let tag = Self::generate_tag(domain_separator.as_bytes());
         Self::unchecked_load_with_stack(tag, stack)
     }
 
+    /// Initialise a stateful hash object from a compiled domain separator.
+    ///
+    /// Unlike [`Self::new`], this neither parses nor hashes the domain separator:
+    /// it clones the sponge and the operations prepared by [`DomainSeparator::compile`].
+    #[must_use]
+    pub fn from_compiled(compiled: &CompiledDomainSeparator<H, U>) -> Self {
+        Self {
+            ds: compiled.inner.sponge.clone(),
+            stack: compiled.inner.stack.clone(),
+            checked: true,
+            _unit: PhantomData,
+        }
+    }
+
+    /// Initialise a stateful hash object from a 32-byte IV, without any instructions.
+    ///
+    /// Operations are not checked: the caller is responsible for enforcing the protocol.
+    pub(crate) fn unchecked(iv: [u8; 32]) -> Self {
+        Self {
+            ds: H::new(iv),
+            stack: VecDeque::new(),
+            checked: false,
+            _unit: PhantomData,
+        }
+    }
+
     /// Finish the block and compress the state.
     pub fn ratchet(&mut self) -> Result<(), DomainSeparatorMismatch> {
+        if !self.checked {
+            self.ds.ratchet_unchecked();
+            return Ok(());
+        }
         match self.stack.pop_front() {
-            Some(Op::Ratchet) => {
+            Some(LabeledOp {
+                op: Op::Ratchet, ..
+            }) => {
                 self.ds.ratchet_unchecked();
                 Ok(())
             }
-            Some(op) => Err(format!("Expected Ratchet, got {op:?}").into()),
-            None => Err("Expected Ratchet, but stack is empty".into()),
+            Some(expected) => Err(Self::mismatch(&expected, Op::Ratchet)),
+            None => Err(DomainSeparatorMismatch::Exhausted { got: Op::Ratchet }),
         }
     }
 
     /// Ratchet and return the sponge state.
-    pub fn preprocess(self) -> Result<&'static [U], DomainSeparatorMismatch> {
-        unimplemented!()
-        // self.ratchet()?;
-        // Ok(self.sponge.tag().clone())
+    ///
+    /// The returned [`PreprocessedState`] holds the compressed sponge state and the operations
+    /// left to perform, and can be used to resume the protocol with [`Self::from_preprocessed`].
+    ///
+    /// Fails if the sponge does not support preprocessing, i.e. if [`DuplexSpongeInterface::tag`] is empty.
+    pub fn preprocess(mut self) -> Result<PreprocessedState<U>, DomainSeparatorMismatch> {
+        self.ratchet()?;
+        let tag = self.ds.tag();
+        if tag.is_empty() {
+            return Err("Preprocessing is not supported by this sponge".into());
+        }
+        Ok(PreprocessedState {
+            tag,
+            stack: core::mem::take(&mut self.stack),
+        })
+    }
+
+    /// Resume a stateful hash object from a state exported with [`Self::preprocess`].
+    pub fn from_preprocessed(
+        preprocessed: &PreprocessedState<U>,
+    ) -> Result<Self, DomainSeparatorMismatch> {
+        let ds = H::load(&preprocessed.tag).ok_or("Invalid preprocessed sponge state")?;
+        Ok(Self {
+            ds,
+            stack: preprocessed.stack.clone(),
+            checked: true,
+            _unit: PhantomData,
+        })
     }
 
     /// Perform secure absorption of the elements in `input`.
     ///
     /// Absorb calls can be batched together, or provided separately for streaming-friendly protocols.
     pub fn absorb(&mut self, input: &[U]) -> Result<(), DomainSeparatorMismatch> {
+        if !self.checked {
+            self.ds.absorb_unchecked(input);
+            return Ok(());
+        }
+        let got = Op::Absorb(input.len());
+        match self.stack.pop_front() {
+            Some(mut next) => match next.op {
+                Op::Absorb(length) if length >= input.len() => {
+                    if length > input.len() {
+                        next.op = Op::Absorb(length - input.len());
+                        self.stack.push_front(next);
+                    }
+                    self.ds.absorb_unchecked(input);
+                    Ok(())
+                }
+                _ => {
+                    self.stack.clear();
+                    Err(Self::mismatch(&next, got))
+                }
+            },
+            None => Err(DomainSeparatorMismatch::Exhausted { got }),
+        }
+    }
+
+    /// Start a variable-length absorption of `len` elements.
+    ///
+    /// The length is absorbed as 4 little-endian bytes,
+    /// and the `len` elements are then expected by [`Self::absorb`], in one or more calls.
+    pub fn absorb_dyn_len(&mut self, len: u32) -> Result<(), DomainSeparatorMismatch>
+    where
+        U: From<u8>,
+    {
+        if !self.checked {
+            self.ds.absorb_unchecked(&len.to_le_bytes().map(U::from));
+            return Ok(());
+        }
+        let got = Op::AbsorbDyn;
         match self.stack.pop_front() {
-            Some(Op::Absorb(length)) if length >= input.len() => {
-                if length > input.len() {
-                    self.stack.push_front(Op::Absorb(length - input.len()));
+            Some(mut next) if next.op == Op::AbsorbDyn => {
+                self.ds.absorb_unchecked(&len.to_le_bytes().map(U::from));
+                if len > 0 {
+                    next.op = Op::Absorb(len as usize);
+                    self.stack.push_front(next);
                 }
-                self.ds.absorb_unchecked(input);
                 Ok(())
             }
-            None => {
-                self.stack.clear();
-                Err(format!(
-                    "Invalid tag. Stack empty, got {:?}",
-                    Op::Absorb(input.len())
-                )
-                .into())
-            }
-            Some(op) => {
+            Some(next) => {
                 self.stack.clear();
-                Err(format!(
-                    "Invalid tag. Got {:?}, expected {:?}",
-                    Op::Absorb(input.len()),
-                    op
-                )
-                .into())
+                Err(Self::mismatch(&next, got))
             }
+            None => Err(DomainSeparatorMismatch::Exhausted { got }),
         }
     }
 
-    /// Send or receive a hint from the proof stream.
-    pub fn hint(&mut self) -> Result<(), DomainSeparatorMismatch> {
+    /// Send or receive a hint of `len` bytes from the proof stream.
+    ///
+    /// Fails if `len` exceeds the bound of the hint, if any.
+    pub fn hint(&mut self, len: usize) -> Result<(), DomainSeparatorMismatch> {
+        if !self.checked {
+            return Ok(());
+        }
+        let got = Op::Hint(None);
         match self.stack.pop_front() {
-            Some(Op::Hint) => Ok(()),
-            Some(op) => Err(format!("Invalid tag. Got Op::Hint, expected {op:?}",).into()),
-            None => Err(format!("Invalid tag. Stack empty, got {:?}", Op::Hint).into()),
+            Some(LabeledOp {
+                op: Op::Hint(Some(max_len)),
+                index,
+                label,
+            }) if len > max_len => Err(DomainSeparatorMismatch::HintTooLong {
+                index,
+                label: label.to_string(),
+                max_len,
+                len,
+            }),
+            Some(LabeledOp {
+                op: Op::Hint(_), ..
+            }) => Ok(()),
+            Some(expected) => Err(Self::mismatch(&expected, got)),
+            None => Err(DomainSeparatorMismatch::Exhausted { got }),
         }
     }
 
@@ -99,36 +247,59 @@ impl<U: Unit, H: DuplexSpongeInterface<U>> HashStateWithInstructions<H, U> {
     /// However, for algebraic hashes, this operation is non-trivial.
     /// This function provides no guarantee of streaming-friendliness.
     pub fn squeeze(&mut self, output: &mut [U]) -> Result<(), DomainSeparatorMismatch> {
+        if !self.checked {
+            self.ds.squeeze_unchecked(output);
+            return Ok(());
+        }
+        let got = Op::Squeeze(output.len());
         match self.stack.pop_front() {
-            Some(Op::Squeeze(length)) if output.len() <= length => {
-                self.ds.squeeze_unchecked(output);
-                if length != output.len() {
-                    self.stack.push_front(Op::Squeeze(length - output.len()));
+            Some(mut next) => match next.op {
+                Op::Squeeze(length) if output.len() <= length => {
+                    self.ds.squeeze_unchecked(output);
+                    if length != output.len() {
+                        next.op = Op::Squeeze(length - output.len());
+                        self.stack.push_front(next);
+                    }
+                    Ok(())
                 }
-                Ok(())
-            }
-            None => {
-                self.stack.clear();
-                Err(format!(
-                    "Invalid tag. Stack empty, got {:?}",
-                    Op::Squeeze(output.len())
-                )
-                .into())
-            }
-            Some(op) => {
-                self.stack.clear();
-                Err(format!(
-                    "Invalid tag. Got {:?}, expected {:?}. The stack remaining is: {:?}",
-                    Op::Squeeze(output.len()),
-                    op,
-                    self.stack
-                )
-                .into())
-            }
+                _ => {
+                    self.stack.clear();
+                    Err(Self::mismatch(&next, got))
+                }
+            },
+            None => Err(DomainSeparatorMismatch::Exhausted { got }),
         }
     }
 
-    fn generate_tag(iop_bytes: &[u8]) -> [u8; 32] {
+    /// Consume the hash state, checking that all operations have been performed.
+    pub fn finish(mut self) -> Result<(), DomainSeparatorMismatch> {
+        let unfinished = self.unfinished();
+        // the error is reported here, not by the drop policy.
+        self.stack.clear();
+        unfinished.map_or(Ok(()), Err)
+    }
+
+    /// The error for the operations left to perform, if any.
+    fn unfinished(&self) -> Option<DomainSeparatorMismatch> {
+        self.stack
+            .front()
+            .map(|next| DomainSeparatorMismatch::Unfinished {
+                index: next.index,
+                label: next.label.to_string(),
+                remaining: self.stack.iter().map(|op| op.op).collect(),
+            })
+    }
+
+    fn mismatch(expected: &LabeledOp, got: Op) -> DomainSeparatorMismatch {
+        DomainSeparatorMismatch::UnexpectedOp {
+            index: expected.index,
+            expected: expected.op,
+            label: expected.label.to_string(),
+            got,
+        }
+    }
+
+    pub(crate) fn generate_tag(iop_bytes: &[u8]) -> [u8; 32] {
         let mut keccak = Keccak::default();
         keccak.absorb_unchecked(iop_bytes);
         let mut tag = [0u8; 32];
@@ -136,10 +307,11 @@ impl<U: Unit, H: DuplexSpongeInterface<U>> HashStateWithInstructions<H, U> {
         tag
     }
 
-    fn unchecked_load_with_stack(tag: [u8; 32], stack: VecDeque<Op>) -> Self {
+    fn unchecked_load_with_stack(tag: [u8; 32], stack: VecDeque<LabeledOp>) -> Self {
         Self {
             ds: H::new(tag),
             stack,
+            checked: true,
             _unit: PhantomData,
         }
     }
@@ -150,15 +322,124 @@ impl<U: Unit, H: DuplexSpongeInterface<U>> HashStateWithInstructions<H, U> {
     }
 }
 
+/// The compressed state of a [`HashStateWithInstructions`] after a ratchet.
+///
+/// It holds the capacity of the sponge and the remaining operations of the domain separator.
+/// This allows to process a common prefix of a protocol (e.g., the statement) once,
+/// and resume from it for every proof.
+#[derive(Clone)]
+pub struct PreprocessedState<U: Unit = u8> {
+    /// The sponge state, as exported by [`DuplexSpongeInterface::tag`].
+    tag: Vec<U>,
+    /// The operations left to perform.
+    stack: VecDeque<LabeledOp>,
+}
+
+impl<U: Unit> PreprocessedState<U> {
+    /// Return the compressed sponge state.
+    #[must_use]
+    pub fn tag(&self) -> &[U] {
+        self.tag.as_slice()
+    }
+}
+
+impl<U: Unit> fmt::Debug for PreprocessedState<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Ensure that the state isn't accidentally logged.
+        write!(
+            f,
+            "Preprocessed sponge state with committed verifier operations: {:?}",
+            self.stack
+        )
+    }
+}
+
+/// A [`DomainSeparator`] parsed and hashed once, from which prover and verifier states are cheaply created.
+///
+/// It holds the 32-byte tag of the domain separator, its operations,
+/// and a sponge already initialised with the tag.
+/// Cloning it only increments a reference count,
+/// and it can be shared across threads whenever the sponge `H` can.
+/// Build it with [`DomainSeparator::compile`].
+#[derive(Clone)]
+pub struct CompiledDomainSeparator<H, U = u8>
+where
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+{
+    inner: Arc<CompiledInner<H, U>>,
+}
+
+struct CompiledInner<H, U>
+where
+    U: Unit,
+    H: DuplexSpongeInterface<U>,
+{
+    domain_separator: DomainSeparator<H, U>,
+    tag: [u8; 32],
+    stack: VecDeque<LabeledOp>,
+    sponge: H,
+}
+
+impl<U: Unit, H: DuplexSpongeInterface<U>> CompiledDomainSeparator<H, U> {
+    pub(crate) fn new(domain_separator: &DomainSeparator<H, U>) -> Self {
+        let tag = HashStateWithInstructions::<H, U>::generate_tag(domain_separator.as_bytes());
+        Self {
+            inner: Arc::new(CompiledInner {
+                domain_separator: domain_separator.clone(),
+                tag,
+                stack: domain_separator.labeled_ops(),
+                sponge: H::new(tag),
+            }),
+        }
+    }
+
+    /// Return the domain separator this was compiled from.
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)] // False positive
+    pub fn domain_separator(&self) -> &DomainSeparator<H, U> {
+        &self.inner.domain_separator
+    }
+
+    /// Return the 32-byte tag used to initialise the sponge.
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)] // False positive
+    pub fn tag(&self) -> &[u8; 32] {
+        &self.inner.tag
+    }
+
+    /// Create a [`crate::ProverState`] instance from the compiled domain separator.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn to_prover_state(&self) -> crate::ProverState<H, U, crate::DefaultRng> {
+        crate::ProverState::from_compiled(self, crate::DefaultRng::default())
+    }
+
+    /// Create a [`crate::VerifierState`] instance from the compiled domain separator and the protocol transcript (bytes).
+    #[must_use]
+    pub fn to_verifier_state<'a>(&self, transcript: &'a [u8]) -> crate::VerifierState<'a, H, U> {
+        crate::VerifierState::from_compiled(self, transcript)
+    }
+}
+
+impl<U: Unit, H: DuplexSpongeInterface<U>> fmt::Debug for CompiledDomainSeparator<H, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Compiled{:?}", self.inner.domain_separator)
+    }
+}
+
 impl<U: Unit, H: DuplexSpongeInterface<U>> Drop for HashStateWithInstructions<H, U> {
     /// Destroy the sponge state.
     fn drop(&mut self) {
-        // it's a bit violent to panic here,
-        // because any other issue in the protocol transcript causing `Safe` to get out of scope
-        // (like another panic) will pollute the traceback.
-        // debug_assert!(self.stack.is_empty());
-        if !self.stack.is_empty() {
-            eprintln!("Unfinished operations:\n {:?}", self.stack);
+        #[cfg(feature = "std")]
+        if let Some(err) = self.unfinished() {
+            match unfinished_policy() {
+                UnfinishedPolicy::Warn => eprintln!("{err}"),
+                // panicking while unwinding would abort and pollute the traceback.
+                UnfinishedPolicy::DebugPanic => debug_assert!(std::thread::panicking(), "{err}"),
+                UnfinishedPolicy::Silent => (),
+                UnfinishedPolicy::Hook(hook) => hook(&err),
+            }
         }
         // XXX. is the compiler going to optimize this out?
         self.ds.zeroize();
@@ -239,6 +520,16 @@ mod tests {
             *self.ratcheted.borrow_mut() = true;
             self
         }
+
+        fn tag(&self) -> Vec<u8> {
+            self.absorbed.borrow().clone()
+        }
+
+        fn load(tag: &[u8]) -> Option<Self> {
+            let sponge = Self::new_inner();
+            sponge.absorbed.borrow_mut().extend_from_slice(tag);
+            Some(sponge)
+        }
     }
 
     #[test]
@@ -285,7 +576,7 @@ mod tests {
         let result = state.squeeze(&mut out);
         assert!(result.is_ok());
 
-        assert_eq!(state.stack.front(), Some(&Op::Squeeze(2)));
+        assert_eq!(state.stack.front().map(|op| op.op), Some(Op::Squeeze(2)));
     }
 
     #[test]
@@ -308,6 +599,43 @@ mod tests {
         assert!(state.stack.is_empty());
     }
 
+    #[test]
+    fn test_finish_checks_stack() {
+        let domsep = DomainSeparator::<DummySponge>::new("test").absorb(2, "x");
+
+        let mut state = HashStateWithInstructions::<DummySponge>::new(&domsep);
+        state.absorb(&[1]).unwrap();
+        assert!(state.finish().is_err());
+
+        let mut state = HashStateWithInstructions::<DummySponge>::new(&domsep);
+        state.absorb(&[1, 2]).unwrap();
+        assert!(state.finish().is_ok());
+    }
+
+    #[test]
+    fn test_unfinished_policy_hook() {
+        use std::cell::Cell;
+
+        // the policy is process-wide, so only record drops happening in this thread.
+        thread_local! {
+            static LEFTOVER: Cell<usize> = const { Cell::new(0) };
+        }
+        fn hook(err: &DomainSeparatorMismatch) {
+            if let DomainSeparatorMismatch::Unfinished { remaining, .. } = err {
+                LEFTOVER.with(|leftover| leftover.set(remaining.len()));
+            }
+        }
+
+        let previous = set_unfinished_policy(UnfinishedPolicy::Hook(hook));
+        let domsep = DomainSeparator::<DummySponge>::new("test")
+            .absorb(1, "x")
+            .squeeze(1, "y");
+        drop(HashStateWithInstructions::<DummySponge>::new(&domsep));
+        set_unfinished_policy(previous);
+
+        assert_eq!(LEFTOVER.with(Cell::get), 2);
+    }
+
     #[test]
     fn test_multiple_absorbs_deplete_stack_properly() {
         let domsep = DomainSeparator::<DummySponge>::new("test").absorb(5, "a");
@@ -315,7 +643,7 @@ mod tests {
 
         let res1 = state.absorb(&[1, 2]);
         assert!(res1.is_ok());
-        assert_eq!(state.stack.front(), Some(&Op::Absorb(3)));
+        assert_eq!(state.stack.front().map(|op| op.op), Some(Op::Absorb(3)));
 
         let res2 = state.absorb(&[3, 4, 5]);
         assert!(res2.is_ok());
@@ -331,7 +659,7 @@ mod tests {
 
         let mut out1 = [0u8; 2];
         assert!(state.squeeze(&mut out1).is_ok());
-        assert_eq!(state.stack.front(), Some(&Op::Squeeze(3)));
+        assert_eq!(state.stack.front().map(|op| op.op), Some(Op::Squeeze(3)));
 
         let mut out2 = [0u8; 3];
         assert!(state.squeeze(&mut out2).is_ok());
@@ -366,7 +694,7 @@ mod tests {
         let state = HashStateWithInstructions::<DummySponge>::from(&domsep);
 
         assert_eq!(state.stack.len(), 1);
-        assert_eq!(state.stack.front(), Some(&Op::Absorb(1)));
+        assert_eq!(state.stack.front().map(|op| op.op), Some(Op::Absorb(1)));
     }
 
     #[test]
@@ -386,27 +714,182 @@ mod tests {
         let mut state = HashStateWithInstructions::<DummySponge>::new(&domsep);
 
         assert_eq!(state.stack.len(), 1);
-        let result = state.hint();
+        let result = state.hint(0);
         assert!(result.is_ok());
         assert!(state.stack.is_empty());
     }
 
+    #[test]
+    fn test_hint_bounded_rejects_long_hints() {
+        let domsep = DomainSeparator::<DummySponge>::new("test")
+            .hint_bounded(4, "short")
+            .hint_bounded(4, "short");
+        let mut state = HashStateWithInstructions::<DummySponge>::new(&domsep);
+
+        assert!(state.hint(4).is_ok());
+        let err = state.hint(5).unwrap_err();
+        assert!(matches!(
+            &err,
+            DomainSeparatorMismatch::HintTooLong {
+                index: 1,
+                max_len: 4,
+                len: 5,
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Hint 'short' at op #1 has 5 bytes, more than the bound of 4"
+        );
+    }
+
     #[test]
     fn test_hint_wrong_op_errors_and_clears_stack() {
         let domsep = DomainSeparator::<DummySponge>::new("test").absorb(1, "x");
         let mut state = HashStateWithInstructions::<DummySponge>::new(&domsep);
 
-        let result = state.hint(); // Should expect Op::Hint, but see Op::Absorb
+        let result = state.hint(0); // Should expect Op::Hint(None), but see Op::Absorb
         assert!(result.is_err());
         assert!(state.stack.is_empty());
     }
 
+    #[test]
+    fn test_absorb_dyn_len_absorbs_the_length_first() {
+        let domsep = DomainSeparator::<DummySponge>::new("test")
+            .absorb_dyn("msg")
+            .absorb_dyn("empty")
+            .squeeze(1, "c");
+        let mut state = HashStateWithInstructions::<DummySponge>::new(&domsep);
+
+        state.absorb_dyn_len(3).unwrap();
+        assert_eq!(state.stack.front().map(|op| op.op), Some(Op::Absorb(3)));
+        state.absorb(&[1]).unwrap();
+        state.absorb(&[2, 3]).unwrap();
+        state.absorb_dyn_len(0).unwrap();
+        assert_eq!(
+            &*state.ds.absorbed.borrow(),
+            &[3, 0, 0, 0, 1, 2, 3, 0, 0, 0, 0]
+        );
+        assert_eq!(state.stack.front().map(|op| op.op), Some(Op::Squeeze(1)));
+    }
+
+    #[test]
+    fn test_absorb_dyn_len_mismatch() {
+        let domsep = DomainSeparator::<DummySponge>::new("test")
+            .absorb_dyn("msg")
+            .squeeze(1, "c");
+        let mut state = HashStateWithInstructions::<DummySponge>::new(&domsep);
+        // the elements must follow the length
+        state.absorb_dyn_len(2).unwrap();
+        assert!(state.absorb(&[1, 2, 3]).is_err());
+
+        // a fixed-size absorb is not a dynamic one
+        let domsep = DomainSeparator::<DummySponge>::new("test").absorb(4, "x");
+        let mut state = HashStateWithInstructions::<DummySponge>::new(&domsep);
+        assert!(state.absorb_dyn_len(0).is_err());
+        assert!(state.stack.is_empty());
+
+        let domsep = DomainSeparator::<DummySponge>::new("test").absorb_dyn("msg");
+        let mut state = HashStateWithInstructions::<DummySponge>::new(&domsep);
+        assert!(state.absorb(&[0; 4]).is_err());
+    }
+
+    #[test]
+    fn test_preprocess_keeps_remaining_stack() {
+        let domsep = DomainSeparator::<DummySponge>::new("test")
+            .absorb(2, "statement")
+            .ratchet()
+            .squeeze(3, "challenge");
+        let mut state = HashStateWithInstructions::<DummySponge>::new(&domsep);
+        state.absorb(&[1, 2]).unwrap();
+
+        let preprocessed = state.preprocess().unwrap();
+        assert_eq!(preprocessed.tag(), &[1, 2]);
+
+        let state =
+            HashStateWithInstructions::<DummySponge>::from_preprocessed(&preprocessed).unwrap();
+        assert_eq!(&*state.ds.absorbed.borrow(), &[1, 2]);
+        assert_eq!(
+            state.finish().unwrap_err().to_string(),
+            "Unfinished operations from 'challenge' at op #2: S3"
+        );
+    }
+
+    #[test]
+    fn test_from_compiled_matches_new() {
+        let domsep = DomainSeparator::<DummySponge>::new("test")
+            .absorb(1, "x")
+            .absorb(2, "y")
+            .squeeze(1, "z");
+        let compiled = domsep.compile();
+        assert_eq!(
+            compiled.tag(),
+            &HashStateWithInstructions::<DummySponge>::generate_tag(domsep.as_bytes())
+        );
+
+        let mut state = HashStateWithInstructions::<DummySponge>::from_compiled(&compiled);
+        let fresh = HashStateWithInstructions::<DummySponge>::new(&domsep);
+        assert_eq!(state.stack, fresh.stack);
+        fresh.finish().unwrap_err();
+
+        // the compiled operations are not consumed by the states created from them.
+        state.absorb(&[1, 2, 3]).unwrap();
+        state.squeeze(&mut [0]).unwrap();
+        state.finish().unwrap();
+        let state = HashStateWithInstructions::<DummySponge>::from_compiled(&compiled);
+        assert_eq!(state.stack.len(), 2);
+        state.finish().unwrap_err();
+    }
+
+    #[test]
+    fn test_preprocess_without_ratchet_errors() {
+        let domsep = DomainSeparator::<DummySponge>::new("test").absorb(1, "x");
+        let state = HashStateWithInstructions::<DummySponge>::new(&domsep);
+        assert!(state.preprocess().is_err());
+    }
+
+    #[test]
+    fn test_mismatch_reports_position_and_label() {
+        let domsep = DomainSeparator::<DummySponge>::new("test")
+            .absorb(2, "statement")
+            .ratchet()
+            .absorb(1, "commitment")
+            .squeeze(32, "challenge");
+        let mut state = HashStateWithInstructions::<DummySponge>::new(&domsep);
+        state.absorb(&[1, 2]).unwrap();
+        state.ratchet().unwrap();
+        state.absorb(&[3]).unwrap();
+
+        let err = state.absorb(&[0; 64]).unwrap_err();
+        assert!(matches!(
+            &err,
+            DomainSeparatorMismatch::UnexpectedOp {
+                index: 3,
+                expected: Op::Squeeze(32),
+                label,
+                got: Op::Absorb(64),
+            } if label == "challenge"
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Expected S32 'challenge' at op #3, got A64"
+        );
+
+        let err = state.squeeze(&mut [0; 1]).unwrap_err();
+        assert!(matches!(
+            err,
+            DomainSeparatorMismatch::Exhausted {
+                got: Op::Squeeze(1)
+            }
+        ));
+    }
+
     #[test]
     fn test_hint_on_empty_stack_errors() {
         let domsep = DomainSeparator::<DummySponge>::new("test");
         let mut state = HashStateWithInstructions::<DummySponge>::new(&domsep);
 
-        let result = state.hint(); // Stack is empty
+        let result = state.hint(0); // Stack is empty
         assert!(result.is_err());
     }
 }
@@ -1,9 +1,10 @@
 use rand::RngCore;
 
 use crate::{
-    duplex_sponge::legacy::DigestBridge, keccak::Keccak, BytesToUnitDeserialize,
-    BytesToUnitSerialize, CommonUnitToBytes, DomainSeparator, DuplexSpongeInterface,
-    HashStateWithInstructions, ProverState, UnitToBytes,
+    duplex_sponge::{legacy::DigestBridge, DuplexSponge, Permutation},
+    keccak::{Keccak, KeccakF1600},
+    BytesToUnitDeserialize, BytesToUnitSerialize, CommonUnitToBytes, DefaultHash, DomainSeparator,
+    DuplexSpongeInterface, HashStateWithInstructions, ProverState, UnitToBytes, VerifierState,
 };
 
 type Sha2 = DigestBridge<sha2::Sha256>;
@@ -221,3 +222,392 @@ fn test_streaming_blake2() {
 fn test_streaming_keccak() {
     test_streaming_absorb_and_squeeze::<Keccak>();
 }
+
+/// A verifier resumed from a preprocessed state should produce the same challenges as a fresh one.
+fn test_preprocess_and_resume<H: DuplexSpongeInterface>()
+where
+    ProverState<H>: BytesToUnitSerialize + UnitToBytes,
+{
+    let domain_separator = DomainSeparator::<H>::new("domain separator")
+        .absorb(32, "statement")
+        .ratchet()
+        .absorb(16, "commitment")
+        .squeeze(32, "challenge")
+        .absorb(1, "response")
+        .squeeze(200, "last challenge");
+
+    let mut prover_state = domain_separator.to_prover_state();
+    prover_state.public_bytes(&[0x42; 32]).unwrap();
+    prover_state.ratchet().unwrap();
+    prover_state.add_bytes(b"yellow submarine").unwrap();
+    let prover_challenge = prover_state.challenge_bytes::<32>().unwrap();
+    prover_state.add_bytes(&[0x01]).unwrap();
+    let prover_last_challenge = prover_state.challenge_bytes::<200>().unwrap();
+    let narg_string = prover_state.narg_string();
+
+    let mut verifier_state = domain_separator.to_verifier_state(&[]);
+    verifier_state.public_bytes(&[0x42; 32]).unwrap();
+    let preprocessed = verifier_state.preprocess().unwrap();
+
+    for _ in 0..2 {
+        let mut verifier_state =
+            VerifierState::<H>::from_preprocessed(&preprocessed, narg_string).unwrap();
+        assert_eq!(
+            verifier_state.next_bytes::<16>().unwrap(),
+            *b"yellow submarine"
+        );
+        assert_eq!(
+            verifier_state.challenge_bytes::<32>().unwrap(),
+            prover_challenge
+        );
+        assert_eq!(verifier_state.next_bytes::<1>().unwrap(), [0x01]);
+        assert_eq!(
+            verifier_state.challenge_bytes::<200>().unwrap(),
+            prover_last_challenge
+        );
+    }
+
+    // A malformed sponge state is rejected.
+    assert!(H::load(&preprocessed.tag()[..1]).is_none());
+}
+
+#[test]
+fn test_preprocess_sha2() {
+    test_preprocess_and_resume::<Sha2>();
+}
+
+#[test]
+fn test_preprocess_blake2() {
+    test_preprocess_and_resume::<Blake2b512>();
+    test_preprocess_and_resume::<Blake2s256>();
+}
+
+/// A sponge written against the original [`DuplexSpongeInterface`] methods.
+#[derive(Clone, Default)]
+struct LegacySponge(Keccak);
+
+impl zeroize::Zeroize for LegacySponge {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl DuplexSpongeInterface for LegacySponge {
+    fn new(iv: [u8; 32]) -> Self {
+        Self(Keccak::new(iv))
+    }
+
+    fn absorb_unchecked(&mut self, input: &[u8]) -> &mut Self {
+        self.0.absorb_unchecked(input);
+        self
+    }
+
+    fn squeeze_unchecked(&mut self, output: &mut [u8]) -> &mut Self {
+        self.0.squeeze_unchecked(output);
+        self
+    }
+
+    fn ratchet_unchecked(&mut self) -> &mut Self {
+        self.0.ratchet_unchecked();
+        self
+    }
+}
+
+#[test]
+fn test_preprocess_unsupported() {
+    let domain_separator = DomainSeparator::<LegacySponge>::new("legacy")
+        .absorb(1, "statement")
+        .ratchet()
+        .squeeze(1, "challenge");
+    let mut verifier_state = domain_separator.to_verifier_state(&[]);
+    verifier_state.public_bytes(&[0x42]).unwrap();
+    assert!(verifier_state.preprocess().is_err());
+    assert!(LegacySponge::load(&[0; 32]).is_none());
+}
+
+#[test]
+fn test_preprocess_keccak() {
+    test_preprocess_and_resume::<Keccak>();
+}
+
+/// Absorption after a ratchet resumes at the previous absorb position,
+/// as in the proofs created by earlier versions.
+#[test]
+fn test_ratchet_test_vector() {
+    let domain_separator = DomainSeparator::<Keccak>::new("ratchet vector")
+        .absorb(32, "public")
+        .ratchet()
+        .absorb(16, "commitment")
+        .squeeze(32, "challenge");
+    let mut prover_state = domain_separator.to_prover_state();
+    prover_state.public_bytes(&[0x42; 32]).unwrap();
+    prover_state.ratchet().unwrap();
+    prover_state.add_bytes(b"yellow submarine").unwrap();
+    let challenge = prover_state.challenge_bytes::<32>().unwrap();
+    assert_eq!(
+        hex::encode(challenge),
+        "46b0def964473fcee3c286c70ac81d459fdfdaa95f4e0299342355a306026999"
+    );
+}
+
+/// States created from a compiled domain separator behave as the ones created from the domain separator,
+/// and the compiled domain separator can be shared across threads.
+#[test]
+fn test_compiled_domain_separator() {
+    let domain_separator = DomainSeparator::<Keccak>::new("domain separator")
+        .absorb(16, "commitment")
+        .squeeze(32, "challenge");
+    let compiled = domain_separator.compile();
+    assert_eq!(
+        compiled.domain_separator().as_bytes(),
+        domain_separator.as_bytes()
+    );
+
+    let mut prover_state = compiled.to_prover_state();
+    prover_state.add_bytes(b"yellow submarine").unwrap();
+    let prover_challenge = prover_state.challenge_bytes::<32>().unwrap();
+    let narg_string = prover_state.narg_string().to_vec();
+
+    let mut verifier_state = domain_separator.to_verifier_state(&narg_string);
+    verifier_state.next_bytes::<16>().unwrap();
+    assert_eq!(
+        verifier_state.challenge_bytes::<32>().unwrap(),
+        prover_challenge
+    );
+
+    std::thread::scope(|scope| {
+        for _ in 0..2 {
+            let compiled = compiled.clone();
+            let narg_string = &narg_string;
+            scope.spawn(move || {
+                let mut verifier_state = compiled.to_verifier_state(narg_string);
+                assert_eq!(
+                    verifier_state.next_bytes::<16>().unwrap(),
+                    *b"yellow submarine"
+                );
+                assert_eq!(
+                    verifier_state.challenge_bytes::<32>().unwrap(),
+                    prover_challenge
+                );
+                verifier_state.finish().unwrap();
+            });
+        }
+    });
+}
+
+/// The Keccak permutation, counting how many times it is called in the current thread.
+#[derive(Clone, Default, zeroize::Zeroize)]
+struct CountingKeccakF1600(KeccakF1600);
+
+thread_local! {
+    static PERMUTATION_CALLS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+impl AsRef<[u8]> for CountingKeccakF1600 {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl AsMut<[u8]> for CountingKeccakF1600 {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.0.as_mut()
+    }
+}
+
+impl Permutation for CountingKeccakF1600 {
+    type U = u8;
+    const N: usize = KeccakF1600::N;
+    const R: usize = KeccakF1600::R;
+
+    fn new(iv: [u8; 32]) -> Self {
+        Self(KeccakF1600::new(iv))
+    }
+
+    fn permute(&mut self) {
+        PERMUTATION_CALLS.with(|calls| calls.set(calls.get() + 1));
+        self.0.permute();
+    }
+}
+
+/// The estimated number of permutation calls matches the one of an actual run,
+/// however the messages are split.
+#[test]
+fn test_permutation_calls() {
+    type H = DuplexSponge<CountingKeccakF1600>;
+
+    let domain_separator = DomainSeparator::<H>::new("domain separator")
+        .absorb(200, "statement")
+        .ratchet()
+        .absorb(136, "commitment")
+        .squeeze(32, "challenge")
+        .hint("hint")
+        .absorb(1, "response")
+        .squeeze(300, "last challenge")
+        .absorb(272, "last message");
+    let narg_size = domain_separator.narg_size();
+    assert_eq!(narg_size.units, 200 + 136 + 1 + 272);
+    assert_eq!(narg_size.hints, 1);
+
+    let mut prover_state = domain_separator.to_prover_state();
+    PERMUTATION_CALLS.with(|calls| calls.set(0));
+    prover_state.add_bytes(&[0; 100]).unwrap();
+    prover_state.add_bytes(&[0; 100]).unwrap();
+    prover_state.ratchet().unwrap();
+    prover_state.add_bytes(&[0; 136]).unwrap();
+    prover_state.challenge_bytes::<32>().unwrap();
+    prover_state.hint_bytes(b"some hint").unwrap();
+    prover_state.add_bytes(&[0]).unwrap();
+    prover_state.challenge_bytes::<100>().unwrap();
+    prover_state.challenge_bytes::<200>().unwrap();
+    prover_state.add_bytes(&[0; 272]).unwrap();
+    assert_eq!(
+        PERMUTATION_CALLS.with(std::cell::Cell::get),
+        domain_separator.permutation_calls()
+    );
+    assert_eq!(
+        prover_state.finish().unwrap().len(),
+        narg_size.with_variable(b"some hint".len())
+    );
+
+    let domain_separator = DomainSeparator::<H>::new("domain separator")
+        .absorb(100, "statement")
+        .absorb_dyn("message")
+        .squeeze(32, "challenge");
+    let mut prover_state = domain_separator.to_prover_state();
+    PERMUTATION_CALLS.with(|calls| calls.set(0));
+    prover_state.add_bytes(&[0; 100]).unwrap();
+    prover_state.add_units_dyn(&[0; 50]).unwrap();
+    prover_state.challenge_bytes::<32>().unwrap();
+    assert_eq!(
+        PERMUTATION_CALLS.with(std::cell::Cell::get),
+        domain_separator.permutation_calls_with(&[50])
+    );
+}
+
+/// Errors in nested sub-protocols report the path of the operation.
+#[test]
+fn test_nested_errors_report_paths() {
+    let domain_separator = DomainSeparator::<DefaultHash>::new("outer protocol").nest(
+        "outer",
+        DomainSeparator::new("inner protocol").repeat(4, "round", |domsep, _| {
+            domsep.absorb(1, "message").squeeze(1, "challenge")
+        }),
+    );
+
+    let mut prover_state = domain_separator.to_prover_state();
+    for _ in 0..3 {
+        prover_state.add_bytes(&[0]).unwrap();
+        prover_state.challenge_bytes::<1>().unwrap();
+    }
+    prover_state.add_bytes(&[0]).unwrap();
+    let err = prover_state.challenge_bytes::<2>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Expected S1 'outer/round[3]/challenge' at op #15, got S2"
+    );
+}
+
+/// Variable-length messages are length-prefixed, so that different splits yield different transcripts.
+#[test]
+fn test_dynamic_messages() {
+    let domain_separator = DomainSeparator::<DefaultHash>::new("dynamic")
+        .absorb_dyn("first")
+        .absorb_dyn("second")
+        .squeeze(16, "challenge");
+    let prove = |first: &[u8], second: &[u8]| {
+        let mut prover_state = domain_separator.to_prover_state();
+        prover_state.add_units_dyn(first).unwrap();
+        prover_state.add_units_dyn(second).unwrap();
+        let challenge = prover_state.challenge_bytes::<16>().unwrap();
+        (prover_state.narg_string().to_vec(), challenge)
+    };
+
+    let (narg_string, challenge) = prove(b"ab", b"c");
+    assert_eq!(narg_string, b"\x02\0\0\0ab\x01\0\0\0c");
+    assert_ne!(challenge, prove(b"a", b"bc").1);
+    assert_ne!(challenge, prove(b"abc", b"").1);
+
+    let mut verifier_state = domain_separator.to_verifier_state(&narg_string);
+    assert_eq!(verifier_state.next_units_vec().unwrap(), b"ab");
+    assert_eq!(verifier_state.next_units_len().unwrap(), 1);
+    assert_eq!(verifier_state.next_bytes().unwrap(), *b"c");
+    assert_eq!(verifier_state.challenge_bytes::<16>().unwrap(), challenge);
+    verifier_state.finish().unwrap();
+
+    // A truncated message, or one longer than announced, is rejected.
+    let mut verifier_state = domain_separator.to_verifier_state(&narg_string[..5]);
+    assert!(verifier_state.next_units_vec().is_err());
+    let mut verifier_state = domain_separator.to_verifier_state(b"\x01\0\0\0ab\x01\0\0\0c");
+    verifier_state.next_units_vec().unwrap();
+    assert!(verifier_state.next_units_vec().is_err());
+}
+
+/// A unit written against the original [`crate::Unit`] methods.
+#[derive(Clone, Default)]
+struct LegacyUnit(u16);
+
+impl zeroize::Zeroize for LegacyUnit {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl crate::Unit for LegacyUnit {
+    fn write(bunch: &[Self], w: &mut impl crate::io::Write) -> Result<(), crate::io::Error> {
+        bunch
+            .iter()
+            .try_for_each(|unit| w.write_all(&unit.0.to_le_bytes()))
+    }
+
+    fn read(r: &mut impl crate::io::Read, bunch: &mut [Self]) -> Result<(), crate::io::Error> {
+        bunch.iter_mut().try_for_each(|unit| {
+            let mut bytes = [0u8; 2];
+            r.read_exact(&mut bytes)?;
+            unit.0 = u16::from_le_bytes(bytes);
+            Ok(())
+        })
+    }
+}
+
+#[test]
+fn test_unit_provided_encoded_size() {
+    use crate::Unit;
+
+    assert_eq!(LegacyUnit::encoded_size(), 2);
+}
+
+/// A domain separator written against the original [`crate::ByteDomainSeparator`] methods.
+struct LegacyDomainSeparator(DomainSeparator);
+
+impl crate::ByteDomainSeparator for LegacyDomainSeparator {
+    fn add_bytes(self, count: usize, label: &str) -> Self {
+        Self(self.0.absorb(count, label))
+    }
+
+    fn hint(self, label: &str) -> Self {
+        Self(self.0.hint(label))
+    }
+
+    fn challenge_bytes(self, count: usize, label: &str) -> Self {
+        Self(self.0.squeeze(count, label))
+    }
+}
+
+#[test]
+fn test_byte_domain_separator_provided_methods() {
+    use crate::ByteDomainSeparator;
+
+    let domain_separator = LegacyDomainSeparator(DomainSeparator::new("legacy"))
+        .add_bytes(1, "a")
+        .hint("b")
+        .challenge_bytes(2, "c");
+    assert_eq!(domain_separator.0.as_bytes(), b"legacy\0A1a\0Hb\0S2c");
+
+    // The checked methods are not derived from the panicking ones.
+    let legacy = || LegacyDomainSeparator(DomainSeparator::new("legacy"));
+    assert!(legacy().try_add_bytes(1, "a").is_err());
+    assert!(legacy().try_hint("b").is_err());
+    assert!(legacy().try_challenge_bytes(2, "c").is_err());
+    assert!(legacy().try_hint_bounded(4, "d").is_err());
+}
@@ -60,13 +60,45 @@ pub trait BytesToUnitSerialize {
 }
 
 /// Methods for adding bytes to the [`DomainSeparator`](crate::DomainSeparator), properly counting group elements.
-pub trait ByteDomainSeparator {
+///
+/// The `try_` methods return an error on invalid counts or labels,
+/// while the others panic.
+/// Their default implementations report the operation as unsupported:
+/// implementations should override them.
+pub trait ByteDomainSeparator: Sized {
     #[must_use]
     fn add_bytes(self, count: usize, label: &str) -> Self;
     #[must_use]
     fn hint(self, label: &str) -> Self;
     #[must_use]
     fn challenge_bytes(self, count: usize, label: &str) -> Self;
+
+    #[must_use]
+    fn hint_bounded(self, max_len: usize, label: &str) -> Self {
+        self.try_hint_bounded(max_len, label)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn try_add_bytes(self, _count: usize, _label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        Err("Checked absorption of bytes is not supported by this domain separator.".into())
+    }
+    fn try_hint(self, _label: &str) -> Result<Self, DomainSeparatorMismatch> {
+        Err("Checked hints are not supported by this domain separator.".into())
+    }
+    fn try_hint_bounded(
+        self,
+        _max_len: usize,
+        _label: &str,
+    ) -> Result<Self, DomainSeparatorMismatch> {
+        Err("Bounded hints are not supported by this domain separator.".into())
+    }
+    fn try_challenge_bytes(
+        self,
+        _count: usize,
+        _label: &str,
+    ) -> Result<Self, DomainSeparatorMismatch> {
+        Err("Checked byte challenges are not supported by this domain separator.".into())
+    }
 }
 
 impl<T: UnitTranscript<u8>> CommonUnitToBytes for T {
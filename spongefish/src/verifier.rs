@@ -1,8 +1,12 @@
+use alloc::{format, vec::Vec};
+use core::marker::PhantomData;
+
 use crate::{
     domain_separator::DomainSeparator,
     duplex_sponge::{DuplexSpongeInterface, Unit},
-    errors::DomainSeparatorMismatch,
-    sho::HashStateWithInstructions,
+    errors::{DomainSeparatorMismatch, ProofResult},
+    io,
+    sho::{CompiledDomainSeparator, HashStateWithInstructions, PreprocessedState},
     traits::{BytesToUnitDeserialize, UnitTranscript},
     DefaultHash,
 };
@@ -12,13 +16,138 @@ use crate::{
 /// Internally, it simply contains a stateful hash.
 /// Given as input an [`DomainSeparator`] and a NARG string, it allows to
 /// de-serialize elements from the NARG string and make them available to the zero-knowledge verifier.
-pub struct VerifierState<'a, H = DefaultHash, U = u8>
+///
+/// By default the NARG string is a byte slice.
+/// Use [`VerifierState::from_reader`] to stream it instead, e.g. from a file or a socket.
+pub struct VerifierState<'a, H = DefaultHash, U = u8, R = &'a [u8]>
 where
     H: DuplexSpongeInterface<U>,
     U: Unit,
 {
     pub(crate) hash_state: HashStateWithInstructions<H, U>,
-    pub(crate) narg_string: &'a [u8],
+    pub(crate) narg_string: R,
+    _narg_string: PhantomData<&'a [u8]>,
+}
+
+/// The source a [`VerifierState`] reads the NARG string from.
+///
+/// It is implemented for byte slices, which return hints without copying them,
+/// and for [`NargReader`], which streams the NARG string out of any [`io::Read`].
+pub trait NargSource: io::Read {
+    /// The hints read from the NARG string.
+    type Hint;
+
+    /// Read the length prefix of a hint, as a 4-byte little-endian integer.
+    fn read_hint_len(&mut self) -> Result<usize, DomainSeparatorMismatch>;
+
+    /// Read the `len` bytes of a hint following its length prefix.
+    fn read_hint(&mut self, len: usize) -> Result<Self::Hint, DomainSeparatorMismatch>;
+
+    /// Fail if the NARG string has not been read entirely.
+    fn check_eof(&mut self) -> Result<(), DomainSeparatorMismatch>;
+}
+
+impl<'a> NargSource for &'a [u8] {
+    type Hint = &'a [u8];
+
+    fn read_hint_len(&mut self) -> Result<usize, DomainSeparatorMismatch> {
+        // Ensure at least 4 bytes are available for the length prefix
+        let Some((len, rest)) = self.split_first_chunk::<4>() else {
+            return Err("Insufficient transcript remaining for hint".into());
+        };
+        *self = rest;
+        Ok(u32::from_le_bytes(*len) as usize)
+    }
+
+    fn read_hint(&mut self, len: usize) -> Result<&'a [u8], DomainSeparatorMismatch> {
+        // Ensure the rest of the slice has `len` bytes
+        if self.len() < len {
+            return Err(format!(
+                "Insufficient transcript remaining, got {}, need {len}",
+                self.len()
+            )
+            .into());
+        }
+
+        // Split the hint and advance the transcript
+        let (hint, remaining) = self.split_at(len);
+        *self = remaining;
+
+        Ok(hint)
+    }
+
+    fn check_eof(&mut self) -> Result<(), DomainSeparatorMismatch> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Trailing bytes: {} bytes of the NARG string were not read",
+                self.len()
+            )
+            .into())
+        }
+    }
+}
+
+/// A NARG string streamed out of a reader.
+///
+/// Prover messages are read as the verifier asks for them,
+/// so that the NARG string is never held in memory as a whole.
+///
+/// The reader does not know where the NARG string ends:
+/// [`VerifierState::finish`] and [`VerifierState::check_eof`] read one more byte to check that there is none,
+/// which blocks on a socket and consumes the first byte of whatever follows the proof.
+/// Use [`VerifierState::finish_without_eof`] when more data may follow.
+#[derive(Debug)]
+pub struct NargReader<R>(pub R);
+
+impl<R: io::Read> NargReader<R> {
+    /// The size of the chunks hints are read in,
+    /// so that a forged length prefix cannot make the verifier allocate more than what is actually sent.
+    const HINT_CHUNK_SIZE: usize = 1 << 16;
+}
+
+impl<R: io::Read> io::Read for NargReader<R> {
+    #[cfg(feature = "std")]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        self.0.read(buf)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), io::Error> {
+        self.0.read_exact(buf)
+    }
+}
+
+impl<R: io::Read> NargSource for NargReader<R> {
+    type Hint = Vec<u8>;
+
+    fn read_hint_len(&mut self) -> Result<usize, DomainSeparatorMismatch> {
+        let mut len = [0u8; 4];
+        self.0.read_exact(&mut len)?;
+        Ok(u32::from_le_bytes(len) as usize)
+    }
+
+    fn read_hint(&mut self, len: usize) -> Result<Vec<u8>, DomainSeparatorMismatch> {
+        let mut remaining = len;
+
+        let mut hint = Vec::new();
+        while remaining > 0 {
+            let chunk = remaining.min(Self::HINT_CHUNK_SIZE);
+            let start = hint.len();
+            hint.resize(start + chunk, 0);
+            self.0.read_exact(&mut hint[start..])?;
+            remaining -= chunk;
+        }
+        Ok(hint)
+    }
+
+    fn check_eof(&mut self) -> Result<(), DomainSeparatorMismatch> {
+        match self.0.read_exact(&mut [0u8; 1]) {
+            Ok(()) => Err("Trailing bytes: the NARG string was not read entirely".into()),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
 }
 
 impl<'a, U: Unit, H: DuplexSpongeInterface<U>> VerifierState<'a, H, U> {
@@ -40,10 +169,113 @@ impl<'a, U: Unit, H: DuplexSpongeInterface<U>> VerifierState<'a, H, U> {
     /// ```
     #[must_use]
     pub fn new(domain_separator: &DomainSeparator<H, U>, narg_string: &'a [u8]) -> Self {
-        let hash_state = HashStateWithInstructions::new(domain_separator);
+        Self::with_source(
+            HashStateWithInstructions::new(domain_separator),
+            narg_string,
+        )
+    }
+
+    /// Creates a new [`VerifierState`] from a compiled domain separator,
+    /// without parsing and hashing the domain separator again.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let compiled = DomainSeparator::<DefaultHash>::new("📝").absorb(1, "🥚").compile();
+    /// let mut verifier_state = VerifierState::from_compiled(&compiled, b"!");
+    /// assert_eq!(verifier_state.next_bytes().unwrap(), *b"!");
+    /// ```
+    #[must_use]
+    pub fn from_compiled(compiled: &CompiledDomainSeparator<H, U>, narg_string: &'a [u8]) -> Self {
+        Self::with_source(
+            HashStateWithInstructions::from_compiled(compiled),
+            narg_string,
+        )
+    }
+
+    /// Creates a new [`VerifierState`] resuming from a sponge state exported with [`VerifierState::preprocess`].
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝").absorb(1, "statement").ratchet().squeeze(16, "challenge");
+    /// let mut verifier_state = domsep.to_verifier_state(&[]);
+    /// verifier_state.public_bytes(&[0x42]).unwrap();
+    /// let preprocessed = verifier_state.preprocess().unwrap();
+    ///
+    /// let mut verifier_state = VerifierState::<DefaultHash>::from_preprocessed(&preprocessed, &[]).unwrap();
+    /// assert!(verifier_state.challenge_bytes::<16>().is_ok());
+    /// ```
+    pub fn from_preprocessed(
+        preprocessed: &PreprocessedState<U>,
+        narg_string: &'a [u8],
+    ) -> Result<Self, DomainSeparatorMismatch> {
+        let hash_state = HashStateWithInstructions::from_preprocessed(preprocessed)?;
+        Ok(Self::with_source(hash_state, narg_string))
+    }
+}
+
+impl<U: Unit, H: DuplexSpongeInterface<U>, R: io::Read> VerifierState<'_, H, U, NargReader<R>> {
+    /// Creates a new [`VerifierState`] reading the NARG string from `reader`.
+    ///
+    /// Prover messages are read as they are needed, and hints are returned as owned vectors.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝").absorb(1, "inhale 🫁").hint("🤫");
+    /// let mut prover_state = domsep.to_prover_state();
+    /// prover_state.add_bytes(&[0x42]).unwrap();
+    /// prover_state.hint_bytes(b"secret").unwrap();
+    /// let narg_string = prover_state.finish().unwrap();
+    ///
+    /// // Any `std::io::Read` works, e.g. a file or a socket.
+    /// let reader = std::io::Cursor::new(narg_string);
+    /// let mut verifier_state = VerifierState::from_reader(&domsep, reader);
+    /// assert_eq!(verifier_state.next_bytes().unwrap(), [0x42]);
+    /// assert_eq!(verifier_state.hint_bytes().unwrap(), b"secret");
+    /// verifier_state.finish().unwrap();
+    /// ```
+    #[must_use]
+    pub fn from_reader(domain_separator: &DomainSeparator<H, U>, reader: R) -> Self {
+        Self::with_source(
+            HashStateWithInstructions::new(domain_separator),
+            NargReader(reader),
+        )
+    }
+
+    /// Consume the verifier state, checking that all operations of the domain separator
+    /// have been performed, and return the reader.
+    ///
+    /// Unlike [`Self::finish`], the reader is not probed for trailing bytes:
+    /// it is left right after the NARG string, e.g. at the beginning of the next proof.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝").absorb(1, "🍝");
+    /// let mut reader = &[0x42, 0x43][..];
+    /// for byte in [0x42, 0x43] {
+    ///     let mut verifier_state = VerifierState::from_reader(&domsep, reader);
+    ///     assert_eq!(verifier_state.next_bytes().unwrap(), [byte]);
+    ///     reader = verifier_state.finish_without_eof().unwrap();
+    /// }
+    /// ```
+    pub fn finish_without_eof(self) -> ProofResult<R> {
+        self.hash_state.finish()?;
+        Ok(self.narg_string.0)
+    }
+}
+
+impl<U: Unit, H: DuplexSpongeInterface<U>, R: NargSource> VerifierState<'_, H, U, R> {
+    pub(crate) const fn with_source(
+        hash_state: HashStateWithInstructions<H, U>,
+        narg_string: R,
+    ) -> Self {
         Self {
             hash_state,
             narg_string,
+            _narg_string: PhantomData,
         }
     }
 
@@ -55,33 +287,100 @@ impl<'a, U: Unit, H: DuplexSpongeInterface<U>> VerifierState<'a, H, U> {
         Ok(())
     }
 
-    /// Read a hint from the NARG string. Returns the number of units read.
-    pub fn hint_bytes(&mut self) -> Result<&'a [u8], DomainSeparatorMismatch> {
-        self.hash_state.hint()?;
+    /// Read the length of a variable-length message, before reading its elements
+    /// with [`Self::fill_next_units`].
+    ///
+    /// The length is not checked against the remaining NARG string:
+    /// callers must not allocate it upfront.
+    pub fn next_units_len(&mut self) -> Result<usize, DomainSeparatorMismatch>
+    where
+        U: From<u8>,
+    {
+        self.next_units_len_bounded(usize::MAX)
+    }
 
-        // Ensure at least 4 bytes are available for the length prefix
-        if self.narg_string.len() < 4 {
-            return Err("Insufficient transcript remaining for hint".into());
+    /// Read the length of a variable-length message, as [`Self::next_units_len`],
+    /// rejecting lengths above `max_len` before absorbing them.
+    pub fn next_units_len_bounded(
+        &mut self,
+        max_len: usize,
+    ) -> Result<usize, DomainSeparatorMismatch>
+    where
+        U: From<u8>,
+    {
+        let mut len = [0u8; 4];
+        io::Read::read_exact(&mut self.narg_string, &mut len)?;
+        let len = u32::from_le_bytes(len);
+        if len as usize > max_len {
+            return Err(format!("Message of {len} units is longer than {max_len}").into());
         }
+        self.hash_state.absorb_dyn_len(len)?;
+        Ok(len as usize)
+    }
 
-        // Read 4-byte little-endian length prefix
-        let len = u32::from_le_bytes(self.narg_string[..4].try_into().unwrap()) as usize;
-        let rest = &self.narg_string[4..];
+    /// Read a variable-length message from the NARG string, prefixed by its length.
+    ///
+    /// The output grows as the message is read,
+    /// so that a forged length cannot cause a large allocation.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domain_separator = DomainSeparator::<DefaultHash>::new("📝").absorb_dyn("📜");
+    /// let mut verifier_state = domain_separator.to_verifier_state(b"\x03\0\0\0abc");
+    /// assert_eq!(verifier_state.next_units_vec().unwrap(), b"abc");
+    ///
+    /// let mut verifier_state = domain_separator.to_verifier_state(b"\xff\xff\xff\xffabc");
+    /// assert!(verifier_state.next_units_vec().is_err());
+    /// ```
+    pub fn next_units_vec(&mut self) -> Result<Vec<U>, DomainSeparatorMismatch>
+    where
+        U: From<u8> + Default,
+    {
+        self.next_units_vec_bounded(usize::MAX)
+    }
 
-        // Ensure the rest of the slice has `len` bytes
-        if rest.len() < len {
-            return Err(format!(
-                "Insufficient transcript remaining, got {}, need {len}",
-                rest.len()
-            )
-            .into());
+    /// Read a variable-length message, as [`Self::next_units_vec`],
+    /// rejecting messages longer than `max_len` before reading them.
+    pub fn next_units_vec_bounded(
+        &mut self,
+        max_len: usize,
+    ) -> Result<Vec<U>, DomainSeparatorMismatch>
+    where
+        U: From<u8> + Default,
+    {
+        const CHUNK_LEN: usize = 1 << 12;
+
+        let len = self.next_units_len_bounded(max_len)?;
+        let mut output = Vec::new();
+        while output.len() < len {
+            let start = output.len();
+            output.resize(start + CHUNK_LEN.min(len - start), U::default());
+            self.fill_next_units(&mut output[start..])?;
         }
+        Ok(output)
+    }
 
-        // Split the hint and advance the transcript
-        let (hint, remaining) = rest.split_at(len);
-        self.narg_string = remaining;
+    /// Read a hint from the NARG string.
+    ///
+    /// Hints longer than the bound set in the domain separator are rejected
+    /// before being read.
+    pub fn hint_bytes(&mut self) -> Result<R::Hint, DomainSeparatorMismatch> {
+        self.hint_bytes_bounded(usize::MAX)
+    }
 
-        Ok(hint)
+    /// Read a hint from the NARG string, as [`Self::hint_bytes`],
+    /// also rejecting hints longer than `max_len` before reading them.
+    pub fn hint_bytes_bounded(
+        &mut self,
+        max_len: usize,
+    ) -> Result<R::Hint, DomainSeparatorMismatch> {
+        let len = self.narg_string.read_hint_len()?;
+        if len > max_len {
+            return Err(format!("Hint of {len} bytes is longer than {max_len}").into());
+        }
+        self.hash_state.hint(len)?;
+        self.narg_string.read_hint(len)
     }
 
     /// Signals the end of the statement.
@@ -92,12 +391,39 @@ impl<'a, U: Unit, H: DuplexSpongeInterface<U>> VerifierState<'a, H, U> {
 
     /// Signals the end of the statement and returns the (compressed) sponge state.
     #[inline]
-    pub fn preprocess(self) -> Result<&'static [U], DomainSeparatorMismatch> {
+    pub fn preprocess(self) -> Result<PreprocessedState<U>, DomainSeparatorMismatch> {
         self.hash_state.preprocess()
     }
+
+    /// Check that the whole NARG string has been read.
+    pub fn check_eof(&mut self) -> ProofResult<()> {
+        Ok(self.narg_string.check_eof()?)
+    }
+
+    /// Consume the verifier state, checking that all operations of the domain separator
+    /// have been performed and that the whole NARG string has been read.
+    ///
+    /// Verifiers should call this once done, otherwise garbage appended to a valid proof goes unnoticed.
+    ///
+    /// ```
+    /// # use spongefish::*;
+    ///
+    /// let domsep = DomainSeparator::<DefaultHash>::new("📝").absorb(1, "🍝");
+    /// let mut verifier_state = domsep.to_verifier_state(&[0x42, 0x43]);
+    /// verifier_state.next_bytes::<1>().unwrap();
+    /// // 0x43 has not been read.
+    /// assert!(verifier_state.finish().is_err());
+    /// ```
+    pub fn finish(mut self) -> ProofResult<()> {
+        let eof = self.check_eof();
+        self.hash_state.finish()?;
+        eof
+    }
 }
 
-impl<H: DuplexSpongeInterface<U>, U: Unit> UnitTranscript<U> for VerifierState<'_, H, U> {
+impl<H: DuplexSpongeInterface<U>, U: Unit, R: NargSource> UnitTranscript<U>
+    for VerifierState<'_, H, U, R>
+{
     /// Add native elements to the sponge without writing them to the NARG string.
     #[inline]
     fn public_units(&mut self, input: &[U]) -> Result<(), DomainSeparatorMismatch> {
@@ -111,7 +437,7 @@ impl<H: DuplexSpongeInterface<U>, U: Unit> UnitTranscript<U> for VerifierState<'
     }
 }
 
-impl<H: DuplexSpongeInterface<U>, U: Unit> core::fmt::Debug for VerifierState<'_, H, U> {
+impl<H: DuplexSpongeInterface<U>, U: Unit, R> core::fmt::Debug for VerifierState<'_, H, U, R> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("VerifierState")
             .field(&self.hash_state)
@@ -119,7 +445,9 @@ impl<H: DuplexSpongeInterface<U>, U: Unit> core::fmt::Debug for VerifierState<'_
     }
 }
 
-impl<H: DuplexSpongeInterface<u8>> BytesToUnitDeserialize for VerifierState<'_, H, u8> {
+impl<H: DuplexSpongeInterface<u8>, R: NargSource> BytesToUnitDeserialize
+    for VerifierState<'_, H, u8, R>
+{
     /// Read the next `input.len()` bytes from the NARG string and return them.
     #[inline]
     fn fill_next_bytes(&mut self, input: &mut [u8]) -> Result<(), DomainSeparatorMismatch> {
@@ -132,6 +460,7 @@ mod tests {
     use std::{cell::RefCell, rc::Rc};
 
     use super::*;
+    use crate::{BytesToUnitSerialize, UnitToBytes};
 
     #[derive(Default, Clone)]
     pub struct DummySponge {
@@ -180,6 +509,16 @@ mod tests {
             *self.ratcheted.borrow_mut() = true;
             self
         }
+
+        fn tag(&self) -> Vec<u8> {
+            self.absorbed.borrow().clone()
+        }
+
+        fn load(tag: &[u8]) -> Option<Self> {
+            let sponge = Self::new_inner();
+            sponge.absorbed.borrow_mut().extend_from_slice(tag);
+            Some(sponge)
+        }
     }
 
     #[test]
@@ -225,6 +564,36 @@ mod tests {
         assert!(vs.ratchet().is_err());
     }
 
+    #[test]
+    fn test_finish_rejects_trailing_bytes() {
+        let ds = DomainSeparator::<DummySponge>::new("x").absorb(1, "a");
+        let mut prover = ds.to_prover_state();
+        prover.add_bytes(b"a").unwrap();
+        let mut narg = prover.finish().unwrap();
+
+        let mut vs = VerifierState::<DummySponge>::new(&ds, &narg);
+        assert!(vs.fill_next_bytes(&mut [0u8; 1]).is_ok());
+        assert!(vs.check_eof().is_ok());
+        assert!(vs.finish().is_ok());
+
+        narg.push(0x00);
+        let mut vs = VerifierState::<DummySponge>::new(&ds, &narg);
+        assert!(vs.fill_next_bytes(&mut [0u8; 1]).is_ok());
+        assert!(vs.check_eof().is_err());
+        assert!(vs.finish().is_err());
+    }
+
+    #[test]
+    fn test_finish_rejects_leftover_ops() {
+        let ds = DomainSeparator::<DummySponge>::new("x")
+            .absorb(1, "a")
+            .squeeze(1, "c");
+        let mut vs = VerifierState::<DummySponge>::new(&ds, b"a");
+        assert!(vs.fill_next_bytes(&mut [0u8; 1]).is_ok());
+        assert!(vs.check_eof().is_ok());
+        assert!(vs.finish().is_err());
+    }
+
     #[test]
     fn test_unit_transcript_public_units() {
         let ds = DomainSeparator::<DummySponge>::new("x").absorb(2, "public");
@@ -332,4 +701,97 @@ mod tests {
             "Expected error for hint length > actual NARG bytes, got: {err}"
         );
     }
+
+    #[test]
+    fn test_reader_matches_slice() {
+        let ds = DomainSeparator::<DefaultHash>::new("stream")
+            .absorb(3, "a")
+            .hint("h")
+            .squeeze(8, "c");
+        let mut prover = ds.to_prover_state();
+        prover.add_bytes(b"abc").unwrap();
+        prover.hint_bytes(b"hint").unwrap();
+        prover.challenge_bytes::<8>().unwrap();
+        let narg = prover.finish().unwrap();
+
+        let mut slice_vs = ds.to_verifier_state(&narg);
+        let mut stream_vs = VerifierState::from_reader(&ds, std::io::Cursor::new(&narg));
+        assert_eq!(
+            slice_vs.next_bytes::<3>().unwrap(),
+            stream_vs.next_bytes::<3>().unwrap()
+        );
+        assert_eq!(
+            slice_vs.hint_bytes().unwrap(),
+            stream_vs.hint_bytes().unwrap()
+        );
+        assert_eq!(
+            slice_vs.challenge_bytes::<8>().unwrap(),
+            stream_vs.challenge_bytes::<8>().unwrap()
+        );
+        assert!(slice_vs.finish().is_ok());
+        assert!(stream_vs.finish().is_ok());
+    }
+
+    #[test]
+    fn test_reader_rejects_trailing_bytes() {
+        let ds = DomainSeparator::<DefaultHash>::new("stream").absorb(1, "a");
+        let mut vs = VerifierState::from_reader(&ds, &[0x42, 0x43][..]);
+        assert!(vs.fill_next_bytes(&mut [0u8; 1]).is_ok());
+        assert!(vs.check_eof().is_err());
+    }
+
+    #[test]
+    fn test_reader_finish_without_eof() {
+        let ds = DomainSeparator::<DefaultHash>::new("stream")
+            .absorb(1, "a")
+            .squeeze(1, "b");
+        let mut reader = &[0x42, 0x43][..];
+        let mut vs = VerifierState::from_reader(&ds, &mut reader);
+        vs.fill_next_bytes(&mut [0u8; 1]).unwrap();
+        // Operations of the domain separator are still checked.
+        assert!(vs.finish_without_eof().is_err());
+        // The byte following the NARG string is left in the reader.
+        assert_eq!(reader, [0x43]);
+
+        let mut vs = VerifierState::from_reader(&ds, &mut reader);
+        vs.fill_next_bytes(&mut [0u8; 1]).unwrap();
+        vs.fill_challenge_bytes(&mut [0u8; 1]).unwrap();
+        assert!(vs.finish_without_eof().is_ok());
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_reader_forged_hint_length() {
+        // The length prefix claims 4GiB, but only 2 bytes follow.
+        let ds = DomainSeparator::<DefaultHash>::new("stream").hint("h");
+        let narg = [0xff, 0xff, 0xff, 0xff, b'a', b'b'];
+        let mut vs = VerifierState::from_reader(&ds, &narg[..]);
+        assert!(vs.hint_bytes().is_err());
+    }
+
+    #[test]
+    fn test_bounded_hint_rejected_before_reading() {
+        let ds = DomainSeparator::<DefaultHash>::new("bounded").hint_bounded(2, "h");
+        let narg = [3, 0, 0, 0, b'a', b'b', b'c'];
+
+        let mut vs = ds.to_verifier_state(&narg);
+        assert!(matches!(
+            vs.hint_bytes(),
+            Err(DomainSeparatorMismatch::HintTooLong { len: 3, .. })
+        ));
+
+        // The reader is left right after the length prefix.
+        let mut reader = &narg[..];
+        let mut vs = VerifierState::from_reader(&ds, &mut reader);
+        assert!(matches!(
+            vs.hint_bytes(),
+            Err(DomainSeparatorMismatch::HintTooLong { len: 3, .. })
+        ));
+        drop(vs);
+        assert_eq!(reader, b"abc");
+
+        let mut vs = ds.to_verifier_state(&[2, 0, 0, 0, b'a', b'b']);
+        assert_eq!(vs.hint_bytes().unwrap(), b"ab");
+        vs.finish().unwrap();
+    }
 }